@@ -1,8 +1,18 @@
-use std::io;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::str::FromStr;
 
+use serde::Deserialize;
 use thiserror::Error;
-use time::format_description::well_known::Iso8601;
-use time::{Date, Month, OffsetDateTime, UtcOffset};
+use time::format_description::well_known::{Iso8601, Rfc2822, Rfc3339};
+use time::format_description::BorrowedFormatItem;
+use time::macros::format_description;
+use time::parsing::Parsed;
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset, Weekday};
+use toml::Spanned;
 
 // Error Types
 #[derive(Error, Debug)]
@@ -10,6 +20,9 @@ pub enum EtError {
     #[error("invalid epoch timestamp: {0}")]
     InvalidEpoch(String),
 
+    #[error("epoch {0} is out of range: dates must fall between {MIN_EPOCH} and {MAX_EPOCH} (years -9999 to 9999)")]
+    EpochOutOfRange(i64),
+
     #[error("invalid duration: {0}")]
     InvalidDuration(String),
 
@@ -22,23 +35,196 @@ pub enum EtError {
     #[error("missing timezone in timestamp: {0}")]
     MissingTimezone(String),
 
+    #[error("unrecognized timestamp: {0} (tried epoch, ISO-8601, RFC 2822, RFC 3339 with a space separator, and YYYY-MM-DD)")]
+    UnrecognizedTimestamp(String),
+
+    #[error("invalid date: {0}")]
+    InvalidDate(String),
+
+    #[error("invalid format: {0}")]
+    InvalidFormat(String),
+
+    #[error("invalid UTC offset: {0}")]
+    InvalidOffset(String),
+
+    #[error("could not determine the local UTC offset")]
+    IndeterminateOffset,
+
+    #[error("unsupported precision: {0}")]
+    UnsupportedPrecision(String),
+
+    #[error("unsupported period: {0}")]
+    UnsupportedPeriod(String),
+
+    #[error("invalid week start: {0} (expected mon or sun)")]
+    InvalidWeekStart(String),
+
+    #[error("invalid year mode: {0} (expected calendar or exact-days)")]
+    InvalidYearMode(String),
+
+    #[error("invalid weekday: {0} (expected a name such as mon or monday)")]
+    InvalidWeekday(String),
+
+    #[error("invalid range: {0}")]
+    InvalidRange(String),
+
     #[error("arithmetic overflow")]
     Overflow,
 
-    #[error("no input provided")]
+    #[error("no input provided on stdin")]
     NoInput,
 
+    #[error("stopped after {0} lines of input; more remain (raise --max-lines)")]
+    TooManyLines(usize),
+
+    #[error("line {0}: {1}")]
+    InvalidLine(usize, String),
+
+    #[error("duration {0}: {1}")]
+    InvalidDurationAt(usize, String),
+
+    #[error("invalid config file: {0}")]
+    InvalidConfig(String),
+
+    #[error("{}: {}", .0.display(), .1)]
+    InFile(PathBuf, String),
+
     #[error("{0}")]
     Io(#[from] io::Error),
 }
 
+impl EtError {
+    /// A short machine-readable name for the kind of error. These codes are
+    /// stable across releases, so scripts can match on them instead of the
+    /// message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EtError::InvalidEpoch(_) => "invalid_epoch",
+            EtError::EpochOutOfRange(_) => "epoch_out_of_range",
+            EtError::InvalidDuration(_) => "invalid_duration",
+            EtError::UnsupportedUnit(_) => "unsupported_unit",
+            EtError::InvalidIso(_) => "invalid_iso",
+            EtError::MissingTimezone(_) => "missing_timezone",
+            EtError::UnrecognizedTimestamp(_) => "unrecognized_timestamp",
+            EtError::InvalidDate(_) => "invalid_date",
+            EtError::InvalidFormat(_) => "invalid_format",
+            EtError::InvalidOffset(_) => "invalid_offset",
+            EtError::IndeterminateOffset => "indeterminate_offset",
+            EtError::UnsupportedPrecision(_) => "unsupported_precision",
+            EtError::UnsupportedPeriod(_) => "unsupported_period",
+            EtError::InvalidWeekStart(_) => "invalid_week_start",
+            EtError::InvalidYearMode(_) => "invalid_year_mode",
+            EtError::InvalidWeekday(_) => "invalid_weekday",
+            EtError::InvalidRange(_) => "invalid_range",
+            EtError::Overflow => "overflow",
+            EtError::NoInput => "no_input",
+            EtError::TooManyLines(_) => "too_many_lines",
+            EtError::InvalidLine(..) => "invalid_line",
+            EtError::InvalidDurationAt(..) => "invalid_duration_at",
+            EtError::InvalidConfig(_) => "invalid_config",
+            EtError::InFile(..) => "in_file",
+            EtError::Io(_) => "io",
+        }
+    }
+
+    /// Render the error as a single-line JSON object with its `code` and
+    /// `message`, e.g. `{"code":"overflow","message":"arithmetic overflow"}`.
+    pub fn to_json(&self) -> String {
+        format!("{{\"code\":\"{}\",\"message\":{}}}", self.code(), json_string(&self.to_string()))
+    }
+}
+
+/// `s` as a quoted JSON string, escaping quotes, backslashes, and control
+/// characters.
+fn json_string(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
 pub type Result<T> = std::result::Result<T, EtError>;
 
-/// Duration offset that can be applied to an epoch timestamp.
+/// The category of a `Duration`, without its magnitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DurationKind {
+    Seconds,
+    Months,
+    Years,
+}
+
+/// The unit a fixed duration was written in, kept so that it can be shown
+/// the same way.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixedUnit {
+    Seconds,
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+}
+
+impl FixedUnit {
+    /// The length of one unit in seconds.
+    pub fn seconds(self) -> i64 {
+        match self {
+            FixedUnit::Seconds => 1,
+            FixedUnit::Minutes => 60,
+            FixedUnit::Hours => 3600,
+            FixedUnit::Days => 86400,
+            FixedUnit::Weeks => 604800,
+        }
+    }
+
+    /// `value` of this unit in seconds, or `EtError::Overflow` beyond the
+    /// `i64` range.
+    fn times(self, value: i64) -> Result<i64> {
+        value.checked_mul(self.seconds()).ok_or(EtError::Overflow)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FixedUnit::Seconds => "second",
+            FixedUnit::Minutes => "minute",
+            FixedUnit::Hours => "hour",
+            FixedUnit::Days => "day",
+            FixedUnit::Weeks => "week",
+        }
+    }
+
+    fn suffix(self) -> char {
+        match self {
+            FixedUnit::Seconds => 's',
+            FixedUnit::Minutes => 'm',
+            FixedUnit::Hours => 'h',
+            FixedUnit::Days => 'd',
+            FixedUnit::Weeks => 'w',
+        }
+    }
+}
+
+/// Duration offset that can be applied to an epoch timestamp.
+///
+/// `Seconds` and `Fixed` durations of the same length compare equal; `Fixed`
+/// only remembers the unit for display, so `7d` shows as `7d` rather than
+/// `604800s`.
+#[derive(Debug, Clone, Copy)]
 pub enum Duration {
-    /// Fixed duration in seconds (s, m, h, d, w)
+    /// Fixed duration in seconds, for sums, fractions, and clock notation
     Seconds(i64),
+    /// A whole number of a fixed unit (s, m, h, d, w), as written
+    Fixed { value: i64, unit: FixedUnit },
     /// Calendar months
     Months(i32),
     /// Calendar years
@@ -48,8 +234,24 @@ pub enum Duration {
 impl Duration {
     /// Parse a duration string.
     ///
-    /// Fixed units: s (seconds), m (minutes), h (hours), d (days), w (weeks)
-    /// Calendar units: M (months), Y (years)
+    /// Fixed units: s (seconds), m (minutes), h (hours), d (days), w (weeks),
+    /// fortnight (14 days), plus word forms such as `hours` or `week`
+    /// Calendar units: M (months), Y (years), decade (10 years), c/century (100 years)
+    ///
+    /// Fixed units accept a decimal fraction such as `1.5h`, rounded to the
+    /// nearest whole second (halves round away from zero, so `0.5s` is 1
+    /// second). Calendar units must be whole numbers. A number with no unit
+    /// is seconds, so the float output of another tool such as `-3600.0` is
+    /// read as `Duration::Seconds(-3600)` under the same rounding, while a
+    /// bare integer stays `3600` whole seconds.
+    ///
+    /// Clock notation `H:M:S` or `M:S` is read as fixed seconds, so
+    /// `01:30:00` is 90 minutes. The leading component may be any size, but
+    /// later components must be below 60: `00:90:00` is rejected.
+    ///
+    /// A whole number of a fixed unit gives `Duration::Fixed`, which keeps
+    /// the unit for display; fractions and clock notation give
+    /// `Duration::Seconds`.
     pub fn parse(s: &str) -> Result<Self> {
         let s = s.trim();
         if s.is_empty() {
@@ -58,17 +260,24 @@ impl Duration {
 
         // Determine sign and strip it
         let (sign, rest) = if let Some(stripped) = s.strip_prefix('+') {
-            (1i64, stripped)
+            (1i128, stripped)
         } else if let Some(stripped) = s.strip_prefix('-') {
-            (-1i64, stripped)
+            (-1i128, stripped)
         } else {
-            (1i64, s)
+            (1i128, s)
         };
 
         if rest.is_empty() {
             return Err(EtError::InvalidDuration(s.to_string()));
         }
 
+        if rest.contains(':') {
+            let seconds = parse_clock(rest, s)?;
+            return i64::try_from(sign * seconds)
+                .map(Duration::Seconds)
+                .map_err(|_| EtError::Overflow);
+        }
+
         // Find where digits end and unit begins
         let digit_end = rest
             .find(|c: char| !c.is_ascii_digit())
@@ -79,63 +288,429 @@ impl Duration {
         }
 
         let value_str = &rest[..digit_end];
-        let unit = &rest[digit_end..];
 
-        let value: i64 = value_str
+        // An optional fraction follows a single decimal point
+        let (fraction, unit) = match rest[digit_end..].strip_prefix('.') {
+            Some(after) => {
+                let fraction_end = after
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(after.len());
+                if fraction_end == 0 {
+                    return Err(EtError::InvalidDuration(s.to_string()));
+                }
+                (Some(&after[..fraction_end]), &after[fraction_end..])
+            }
+            None => (None, &rest[digit_end..]),
+        };
+
+        // The value is all digits, so the only way parsing fails is a number
+        // too large to represent. Work in i128 so that the sign and unit
+        // multiplier can be applied before range-checking the result; this
+        // keeps i64::MIN seconds representable.
+        let magnitude: i128 = value_str
             .parse()
-            .map_err(|_| EtError::InvalidDuration(s.to_string()))?;
+            .map_err(|_| EtError::Overflow)?;
+        let value = sign * magnitude;
+
+        // Half a month or a year has no fixed length
+        let whole = || match fraction {
+            Some(_) => Err(EtError::InvalidDuration(format!(
+                "{s}: calendar units must be whole numbers"
+            ))),
+            None => Ok(value),
+        };
 
         // Calendar units (case-sensitive: M for months, Y for years)
         match unit {
             "M" | "mo" | "month" | "months" => {
-                let months = i32::try_from(sign * value)
+                let months = i32::try_from(whole()?)
                     .map_err(|_| EtError::Overflow)?;
                 return Ok(Duration::Months(months));
             }
             "Y" | "y" | "yr" | "year" | "years" => {
-                let years = i32::try_from(sign * value)
+                let years = i32::try_from(whole()?)
                     .map_err(|_| EtError::Overflow)?;
                 return Ok(Duration::Years(years));
             }
+            // Decades and centuries fold into whole years
+            "decade" | "decades" | "c" | "century" | "centuries" => {
+                let per_unit = if unit.starts_with('d') { 10 } else { 100 };
+                let years = whole()?
+                    .checked_mul(per_unit)
+                    .and_then(|v| i32::try_from(v).ok())
+                    .ok_or(EtError::Overflow)?;
+                return Ok(Duration::Years(years));
+            }
             _ => {}
         }
 
-        // Fixed-duration units
-        let multiplier: i64 = match unit.to_lowercase().as_str() {
-            "s" | "" => 1,
-            "m" => 60,
-            "h" => 3600,
-            "d" => 86400,
-            "w" => 604800,
+        // Fixed-duration units; a fortnight is kept as two weeks
+        let (fixed_unit, per_unit) = match unit.to_lowercase().as_str() {
+            "s" | "" | "second" | "seconds" => (FixedUnit::Seconds, 1),
+            "m" | "minute" | "minutes" => (FixedUnit::Minutes, 1),
+            "h" | "hour" | "hours" => (FixedUnit::Hours, 1),
+            "d" | "day" | "days" => (FixedUnit::Days, 1),
+            "w" | "week" | "weeks" | "sennight" | "sennights" => (FixedUnit::Weeks, 1),
+            "fortnight" | "fortnights" => (FixedUnit::Weeks, 2),
             other => {
                 return Err(EtError::UnsupportedUnit(other.to_string()));
             }
         };
+        let multiplier = (fixed_unit.seconds() * per_unit) as i128;
 
-        let seconds = sign
-            .checked_mul(value)
-            .and_then(|v| v.checked_mul(multiplier))
-            .ok_or(EtError::Overflow)?;
+        let seconds = match fraction {
+            None => value.checked_mul(multiplier),
+            Some(fraction) => {
+                // Digits past 18 places cannot change the rounded result
+                let fraction = &fraction[..fraction.len().min(18)];
+                let scale = 10i128.pow(fraction.len() as u32);
+                let fraction: i128 = fraction.parse().map_err(|_| EtError::Overflow)?;
+                magnitude
+                    .checked_mul(scale)
+                    .and_then(|v| v.checked_add(fraction))
+                    .and_then(|v| v.checked_mul(multiplier))
+                    .map(|scaled| sign * ((scaled + scale / 2) / scale))
+            }
+        }
+        .and_then(|v| i64::try_from(v).ok())
+        .ok_or(EtError::Overflow)?;
 
-        Ok(Duration::Seconds(seconds))
+        // A fraction rounds to the second, so it no longer counts whole units
+        match fraction {
+            Some(_) => Ok(Duration::Seconds(seconds)),
+            None => Ok(Duration::Fixed { value: seconds / fixed_unit.seconds(), unit: fixed_unit }),
+        }
     }
 
-    /// Return the seconds value if this is a fixed duration.
-    pub fn as_seconds(&self) -> Option<i64> {
+    /// Parse several duration tokens and add them together, e.g.
+    /// `["1h", "30m"]` gives 5400 seconds.
+    ///
+    /// Every token must be of the same kind: all fixed, all months, or all
+    /// years. Each token carries its own sign, so `["-1h", "30m"]` is -30
+    /// minutes.
+    pub fn parse_sum(inputs: &[&str]) -> Result<Self> {
+        let (first, rest) = inputs
+            .split_first()
+            .ok_or_else(|| EtError::InvalidDuration("empty".to_string()))?;
+
+        let mut total = Duration::parse(first)?;
+        for input in rest {
+            let next = Duration::parse(input)?;
+            total = match (total.resolved()?, next.resolved()?) {
+                (Duration::Seconds(a), Duration::Seconds(b)) => {
+                    Duration::Seconds(a.checked_add(b).ok_or(EtError::Overflow)?)
+                }
+                (Duration::Months(a), Duration::Months(b)) => {
+                    Duration::Months(a.checked_add(b).ok_or(EtError::Overflow)?)
+                }
+                (Duration::Years(a), Duration::Years(b)) => {
+                    Duration::Years(a.checked_add(b).ok_or(EtError::Overflow)?)
+                }
+                _ => {
+                    return Err(EtError::InvalidDuration(format!(
+                        "cannot add {} to {}: units must all be fixed, all months, or all years",
+                        input.trim(),
+                        first.trim()
+                    )));
+                }
+            };
+        }
+
+        Ok(total)
+    }
+
+    /// Parse an ISO 8601 duration such as `PT1H30M` or `P1Y2M3DT4H5M6S`.
+    ///
+    /// A `Duration` holds only one kind, so this returns the parts to apply
+    /// in order: years, then months, then one fixed offset summing weeks,
+    /// days, hours, minutes, and seconds. Only the parts present are
+    /// returned. `M` means months before the `T` and minutes after it, so
+    /// `P1M` is a month and `PT1M` a minute. A leading `-` negates every
+    /// part. Fractional values are not supported.
+    pub fn parse_iso8601(s: &str) -> Result<Vec<Duration>> {
+        let input = s.trim();
+        let invalid = || EtError::InvalidDuration(input.to_string());
+        let (sign, rest) = match input.strip_prefix('-') {
+            Some(rest) => (-1, rest),
+            None => (1, input.strip_prefix('+').unwrap_or(input)),
+        };
+        let rest = rest.strip_prefix('P').ok_or_else(invalid)?;
+        let (date, time) = match rest.split_once('T') {
+            Some((_, "")) => return Err(invalid()),
+            Some((date, time)) => (date, time),
+            None => (rest, ""),
+        };
+        if date.is_empty() && time.is_empty() {
+            return Err(invalid());
+        }
+
+        let mut parts = Vec::new();
+        let mut seconds: Option<i64> = None;
+        let mut add_seconds = |value: i64, per_unit: i64| {
+            let total = value
+                .checked_mul(per_unit)
+                .and_then(|v| v.checked_add(seconds.unwrap_or(0)))
+                .ok_or(EtError::Overflow)?;
+            seconds = Some(total);
+            Ok::<_, EtError>(())
+        };
+
+        for (designator, value) in iso8601_fields(date, "YMWD", input)? {
+            let calendar = || i32::try_from(value * sign).map_err(|_| EtError::Overflow);
+            match designator {
+                'Y' => parts.push(Duration::Years(calendar()?)),
+                'M' => parts.push(Duration::Months(calendar()?)),
+                'W' => add_seconds(value, 604800)?,
+                _ => add_seconds(value, 86400)?,
+            }
+        }
+        for (designator, value) in iso8601_fields(time, "HMS", input)? {
+            match designator {
+                'H' => add_seconds(value, 3600)?,
+                'M' => add_seconds(value, 60)?,
+                _ => add_seconds(value, 1)?,
+            }
+        }
+
+        if let Some(seconds) = seconds {
+            parts.push(Duration::Seconds(seconds.checked_mul(sign).ok_or(EtError::Overflow)?));
+        }
+        Ok(parts)
+    }
+
+    /// Write as an ISO 8601 duration: `P2M` for months, `P1Y` for years, and
+    /// `format_iso8601_duration` for fixed durations. A fixed duration too
+    /// long to count in seconds keeps its own unit, e.g. `P…W`.
+    pub fn to_iso8601(&self) -> String {
+        let calendar = |value: i32, designator: char| {
+            let sign = if value < 0 { "-" } else { "" };
+            format!("{sign}P{}{designator}", value.unsigned_abs())
+        };
+        match *self {
+            Duration::Seconds(s) => format_iso8601_duration(s),
+            Duration::Fixed { value, unit } => match unit.times(value) {
+                Ok(seconds) => format_iso8601_duration(seconds),
+                Err(_) => {
+                    let sign = if value < 0 { "-" } else { "" };
+                    let (time, designator) = match unit {
+                        FixedUnit::Seconds => ("T", 'S'),
+                        FixedUnit::Minutes => ("T", 'M'),
+                        FixedUnit::Hours => ("T", 'H'),
+                        FixedUnit::Days => ("", 'D'),
+                        FixedUnit::Weeks => ("", 'W'),
+                    };
+                    format!("{sign}P{time}{}{designator}", value.unsigned_abs())
+                }
+            },
+            Duration::Months(m) => calendar(m, 'M'),
+            Duration::Years(y) => calendar(y, 'Y'),
+        }
+    }
+
+    /// The same duration in the opposite direction.
+    pub fn negate(self) -> Result<Self> {
+        match self {
+            Duration::Seconds(s) => s.checked_neg().map(Duration::Seconds),
+            Duration::Fixed { value, unit } => value.checked_neg().map(|value| Duration::Fixed { value, unit }),
+            Duration::Months(m) => m.checked_neg().map(Duration::Months),
+            Duration::Years(y) => y.checked_neg().map(Duration::Years),
+        }
+        .ok_or(EtError::Overflow)
+    }
+
+    /// The same duration repeated `factor` times. A negative factor also
+    /// flips the direction.
+    pub fn scale(self, factor: i32) -> Result<Self> {
+        match self {
+            Duration::Seconds(s) => s.checked_mul(factor as i64).map(Duration::Seconds),
+            Duration::Fixed { value, unit } => value
+                .checked_mul(factor as i64)
+                .filter(|value| value.checked_mul(unit.seconds()).is_some())
+                .map(|value| Duration::Fixed { value, unit }),
+            Duration::Months(m) => m.checked_mul(factor).map(Duration::Months),
+            Duration::Years(y) => y.checked_mul(factor).map(Duration::Years),
+        }
+        .ok_or(EtError::Overflow)
+    }
+
+    /// The category of this duration.
+    pub fn kind(&self) -> DurationKind {
         match self {
-            Duration::Seconds(s) => Some(*s),
+            Duration::Seconds(_) | Duration::Fixed { .. } => DurationKind::Seconds,
+            Duration::Months(_) => DurationKind::Months,
+            Duration::Years(_) => DurationKind::Years,
+        }
+    }
+
+    /// Whether this duration uses calendar arithmetic (months or years).
+    pub fn is_calendar(&self) -> bool {
+        !self.is_fixed()
+    }
+
+    /// Whether this duration is a fixed number of seconds.
+    pub fn is_fixed(&self) -> bool {
+        self.kind() == DurationKind::Seconds
+    }
+
+    /// Return the seconds value if this is a fixed duration whose length
+    /// fits in an `i64`.
+    pub fn as_seconds(&self) -> Option<i64> {
+        match self.resolved() {
+            Ok(Duration::Seconds(s)) => Some(s),
             _ => None,
         }
     }
+
+    /// Approximate length in seconds, using the average Gregorian month
+    /// (30.436875 days) and year (365.2425 days) for calendar durations.
+    /// Exact for fixed durations that fit in an `i64`; longer ones saturate.
+    pub fn as_seconds_approx(&self) -> i64 {
+        self.length().clamp(i64::MIN.into(), i64::MAX.into()) as i64
+    }
+
+    /// The length `as_seconds_approx` describes, as an `i128` so that no
+    /// fixed duration saturates.
+    fn length(&self) -> i128 {
+        match *self {
+            Duration::Seconds(s) => s.into(),
+            Duration::Fixed { value, unit } => i128::from(value) * i128::from(unit.seconds()),
+            Duration::Months(m) => i128::from(m) * 2_629_746,
+            Duration::Years(y) => i128::from(y) * 31_556_952,
+        }
+    }
+
+    /// The same duration with a `Fixed` unit folded into plain `Seconds`, or
+    /// `EtError::Overflow` if `value * unit` does not fit in an `i64`.
+    fn resolved(self) -> Result<Self> {
+        match self {
+            Duration::Fixed { value, unit } => unit.times(value).map(Duration::Seconds),
+            _ => Ok(self),
+        }
+    }
+
+    /// Apply this duration to an epoch timestamp.
+    ///
+    /// Equivalent to `apply_duration(epoch, self)`, but reads naturally when
+    /// chaining several offsets:
+    ///
+    /// ```
+    /// use et::Duration;
+    ///
+    /// let month = Duration::parse("+1M")?;
+    /// let day = Duration::parse("-1d")?;
+    ///
+    /// // 2024-01-31T00:00:00Z + 1 month - 1 day = 2024-02-28T00:00:00Z
+    /// let epoch = month.apply_to(1706659200).and_then(|e| day.apply_to(e))?;
+    /// assert_eq!(epoch, 1709078400);
+    /// # Ok::<(), et::EtError>(())
+    /// ```
+    pub fn apply_to(self, epoch: i64) -> Result<i64> {
+        apply_duration(epoch, self)
+    }
+}
+
+/// Split one half of an ISO 8601 duration (before or after the `T`) into
+/// `(designator, value)` pairs, checking that each designator is one of
+/// `designators`, appears at most once, and comes in that order. `input`
+/// is the whole duration, for error messages.
+fn iso8601_fields(part: &str, designators: &str, input: &str) -> Result<Vec<(char, i64)>> {
+    let invalid = || EtError::InvalidDuration(input.to_string());
+    let mut fields = Vec::new();
+    let mut allowed = designators;
+    let mut rest = part;
+
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let designator = rest[digits..].chars().next().ok_or_else(invalid)?;
+        if digits == 0 {
+            return Err(invalid());
+        }
+        let position = allowed.find(designator).ok_or_else(invalid)?;
+        let value: i64 = rest[..digits].parse().map_err(|_| EtError::Overflow)?;
+        fields.push((designator, value));
+        allowed = &allowed[position + 1..];
+        rest = &rest[digits + designator.len_utf8()..];
+    }
+
+    Ok(fields)
+}
+
+/// Read unsigned clock notation (`H:M:S` or `M:S`) as seconds. `input` is
+/// the whole duration, for error messages.
+fn parse_clock(clock: &str, input: &str) -> Result<i128> {
+    let parts: Vec<&str> = clock.split(':').collect();
+    if !(2..=3).contains(&parts.len())
+        || parts.iter().any(|p| p.is_empty() || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return Err(EtError::InvalidDuration(input.to_string()));
+    }
+
+    let mut seconds: i128 = parts[0].parse().map_err(|_| EtError::Overflow)?;
+    for part in &parts[1..] {
+        let value: i128 = part.parse().map_err(|_| EtError::Overflow)?;
+        if value >= 60 {
+            return Err(EtError::InvalidDuration(format!(
+                "{input}: minutes and seconds must be below 60"
+            )));
+        }
+        seconds = seconds
+            .checked_mul(60)
+            .and_then(|v| v.checked_add(value))
+            .ok_or(EtError::Overflow)?;
+    }
+
+    Ok(seconds)
+}
+
+/// Durations are ordered by their length in seconds, as `as_seconds_approx`
+/// gives it but without saturating. Durations of the same kind compare
+/// exactly; across kinds the order is approximate (`+30d` sorts before
+/// `+1M`). Ties between kinds put seconds before months before years.
+impl Ord for Duration {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.length(), self.kind()).cmp(&(other.length(), other.kind()))
+    }
+}
+
+impl PartialOrd for Duration {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Two durations are equal when they are of the same kind and length, so
+/// `60m`, `1h`, and `Seconds(3600)` are all equal. This agrees with `Ord`:
+/// two durations are equal exactly when they compare as `Equal`.
+impl PartialEq for Duration {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Duration {}
+
+/// The normalized form, which `Duration::parse` reads back: the amount and
+/// its unit, e.g. `7d`, `-2M`, or `5400s` for `1h30m`. A `Fixed` duration
+/// keeps the unit it was written in.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Duration::Seconds(s) => write!(f, "{s}s"),
+            Duration::Fixed { value, unit } => write!(f, "{value}{}", unit.suffix()),
+            Duration::Months(m) => write!(f, "{m}M"),
+            Duration::Years(y) => write!(f, "{y}Y"),
+        }
+    }
 }
 
 /// Add months to a date, clamping day to valid range for the resulting month.
+/// Also returns whether the day had to be clamped.
 ///
 /// Examples:
 /// - Jan 31 + 1 month → Feb 28 (or Feb 29 in leap year)
 /// - Mar 31 + 1 month → Apr 30
 /// - Dec 15 + 1 month → Jan 15 (next year)
-fn add_months_to_date(date: Date, months: i32) -> Result<Date> {
+fn add_months_to_date(date: Date, months: i32) -> Result<(Date, bool)> {
     let year = date.year();
     let month = date.month() as i32; // 1-12
     let day = date.day();
@@ -154,16 +729,18 @@ fn add_months_to_date(date: Date, months: i32) -> Result<Date> {
     let max_day = new_month.length(new_year);
     let new_day = day.min(max_day);
 
-    Date::from_calendar_date(new_year, new_month, new_day)
-        .map_err(|_| EtError::Overflow)
+    let new_date = Date::from_calendar_date(new_year, new_month, new_day)
+        .map_err(|_| EtError::Overflow)?;
+    Ok((new_date, new_day != day))
 }
 
-/// Add years to a date, clamping day for leap year edge cases.
+/// Add years to a date, clamping day for leap year edge cases. Also returns
+/// whether the day had to be clamped.
 ///
 /// Examples:
 /// - Feb 29 2024 + 1 year → Feb 28 2025
 /// - Feb 28 2023 + 1 year → Feb 28 2024
-fn add_years_to_date(date: Date, years: i32) -> Result<Date> {
+fn add_years_to_date(date: Date, years: i32) -> Result<(Date, bool)> {
     let new_year = date.year()
         .checked_add(years)
         .ok_or(EtError::Overflow)?;
@@ -174,8 +751,61 @@ fn add_years_to_date(date: Date, years: i32) -> Result<Date> {
     let max_day = month.length(new_year);
     let new_day = day.min(max_day);
 
-    Date::from_calendar_date(new_year, month, new_day)
-        .map_err(|_| EtError::Overflow)
+    let new_date = Date::from_calendar_date(new_year, month, new_day)
+        .map_err(|_| EtError::Overflow)?;
+    Ok((new_date, new_day != day))
+}
+
+/// How a year offset is applied: on the calendar, keeping the date (Feb 29
+/// clamps to Feb 28), or as the exact number of days the years span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum YearMode {
+    #[default]
+    Calendar,
+    ExactDays,
+}
+
+impl FromStr for YearMode {
+    type Err = EtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "calendar" => Ok(YearMode::Calendar),
+            "exact-days" => Ok(YearMode::ExactDays),
+            other => Err(EtError::InvalidYearMode(other.to_string())),
+        }
+    }
+}
+
+impl Duration {
+    /// A year offset as the whole days it spans from `epoch`, for
+    /// `YearMode::ExactDays`: 366 for a year that takes in a Feb 29, 365
+    /// otherwise. A Feb 29 start has no counterpart in a common year, so it
+    /// lands on Mar 1 instead of clamping to Feb 28. Other durations are
+    /// returned unchanged.
+    pub fn in_exact_days(self, epoch: i64) -> Result<Self> {
+        let Duration::Years(years) = self else {
+            return Ok(self);
+        };
+        let date = utc_datetime(epoch)?.date();
+        let year = date.year().checked_add(years).ok_or(EtError::Overflow)?;
+        let target = Date::from_calendar_date(year, date.month(), date.day())
+            .or_else(|_| Date::from_calendar_date(year, Month::March, 1))
+            .map_err(|_| EtError::Overflow)?;
+        Ok(Duration::Fixed { value: (target - date).whole_days(), unit: FixedUnit::Days })
+    }
+}
+
+/// The earliest epoch that can be shown as a date: -9999-01-01T00:00:00Z.
+pub const MIN_EPOCH: i64 = -377_705_116_800;
+
+/// The latest epoch that can be shown as a date: 9999-12-31T23:59:59Z.
+pub const MAX_EPOCH: i64 = 253_402_300_799;
+
+/// The UTC date-time at `epoch`, or `EtError::EpochOutOfRange` outside
+/// `MIN_EPOCH..=MAX_EPOCH`.
+fn utc_datetime(epoch: i64) -> Result<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(epoch).map_err(|_| EtError::EpochOutOfRange(epoch))
 }
 
 /// Get the current Unix epoch time in seconds.
@@ -185,106 +815,1844 @@ pub fn now() -> i64 {
 
 /// Apply a duration offset to an epoch timestamp.
 pub fn apply_duration(epoch: i64, duration: Duration) -> Result<i64> {
-    match duration {
+    Ok(apply_duration_reported(epoch, duration)?.0)
+}
+
+/// Apply a duration offset to an epoch timestamp, clamping to `i64::MIN` or
+/// `i64::MAX` instead of failing with `EtError::Overflow`. For display paths
+/// where a pinned value is more useful than an error; `apply_duration` stays
+/// the default.
+///
+/// Calendar durations from an epoch outside the calendar range fall back to
+/// their average length (see `Duration::as_seconds_approx`).
+pub fn apply_duration_saturating(epoch: i64, duration: Duration) -> i64 {
+    match apply_duration(epoch, duration) {
+        Ok(result) => result,
+        Err(EtError::EpochOutOfRange(_)) => epoch.saturating_add(duration.as_seconds_approx()),
+        Err(_) if duration.as_seconds_approx() < 0 => i64::MIN,
+        Err(_) => i64::MAX,
+    }
+}
+
+/// Apply a duration offset to an epoch timestamp, also returning whether
+/// calendar arithmetic had to clamp the day of month (e.g. Jan 31 + 1M).
+/// Fixed durations never clamp.
+pub fn apply_duration_reported(epoch: i64, duration: Duration) -> Result<(i64, bool)> {
+    match duration.resolved()? {
+        // Plain integer arithmetic, so epochs outside the calendar range
+        // that `OffsetDateTime` supports still work
         Duration::Seconds(secs) => {
-            epoch.checked_add(secs).ok_or(EtError::Overflow)
+            let result = epoch.checked_add(secs).ok_or(EtError::Overflow)?;
+            Ok((result, false))
+        }
+        _ => {
+            let dt = utc_datetime(epoch)?;
+            let (new_dt, clamped) = apply_duration_dt_reported(dt, duration)?;
+            Ok((new_dt.unix_timestamp(), clamped))
         }
-        Duration::Months(months) => {
-            let dt = OffsetDateTime::from_unix_timestamp(epoch)
-                .map_err(|_| EtError::InvalidEpoch(epoch.to_string()))?;
+    }
+}
 
-            let new_date = add_months_to_date(dt.date(), months)?;
-            let new_dt = new_date
-                .with_time(dt.time())
-                .assume_offset(UtcOffset::UTC);
+/// Apply a duration offset to a date-time. Calendar durations move the date
+/// in `dt`'s own offset and keep its time of day; the offset is preserved.
+pub fn apply_duration_dt(dt: OffsetDateTime, duration: Duration) -> Result<OffsetDateTime> {
+    Ok(apply_duration_dt_reported(dt, duration)?.0)
+}
 
-            Ok(new_dt.unix_timestamp())
+fn apply_duration_dt_reported(dt: OffsetDateTime, duration: Duration) -> Result<(OffsetDateTime, bool)> {
+    let (new_date, clamped) = match duration {
+        Duration::Seconds(_) | Duration::Fixed { .. } => {
+            let secs = duration.as_seconds().ok_or(EtError::Overflow)?;
+            let result = dt
+                .checked_add(time::Duration::seconds(secs))
+                .ok_or(EtError::Overflow)?;
+            return Ok((result, false));
         }
-        Duration::Years(years) => {
-            let dt = OffsetDateTime::from_unix_timestamp(epoch)
-                .map_err(|_| EtError::InvalidEpoch(epoch.to_string()))?;
+        Duration::Months(months) => add_months_to_date(dt.date(), months)?,
+        Duration::Years(years) => add_years_to_date(dt.date(), years)?,
+    };
+    Ok((new_date.with_time(dt.time()).assume_offset(dt.offset()), clamped))
+}
+
+/// What applying a duration to an epoch did, for explaining the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApplyReport {
+    pub before: i64,
+    pub after: i64,
+    pub duration: Duration,
+    /// Whether the day of month was clamped because it does not exist in
+    /// the target month (e.g. Jan 31 + 1M).
+    pub clamped: bool,
+}
 
-            let new_date = add_years_to_date(dt.date(), years)?;
-            let new_dt = new_date
-                .with_time(dt.time())
-                .assume_offset(UtcOffset::UTC);
+impl ApplyReport {
+    /// Describe the operation in a sentence, e.g. "Subtracting 1 calendar
+    /// month: 2024-03-31T00:00:00Z → 2024-02-29T00:00:00Z, clamping day 31
+    /// to 29".
+    pub fn describe(&self) -> Result<String> {
+        let (verb, amount) = match self.duration {
+            Duration::Seconds(s) => (s < 0, plural(s.unsigned_abs(), "second")),
+            Duration::Fixed { value, unit } => (value < 0, plural(value.unsigned_abs(), unit.name())),
+            Duration::Months(m) => (m < 0, plural(m.unsigned_abs() as u64, "calendar month")),
+            Duration::Years(y) => (y < 0, plural(y.unsigned_abs() as u64, "calendar year")),
+        };
+        let verb = if verb { "Subtracting" } else { "Adding" };
 
-            Ok(new_dt.unix_timestamp())
+        let mut text = format!(
+            "{verb} {amount}: {} → {}",
+            format_iso(self.before)?,
+            format_iso(self.after)?
+        );
+        if self.clamped {
+            let from = decompose(self.before)?.day;
+            let to = decompose(self.after)?.day;
+            text.push_str(&format!(", clamping day {from} to {to}"));
         }
+        Ok(text)
     }
 }
 
+fn plural(count: u64, unit: &str) -> String {
+    if count == 1 { format!("1 {unit}") } else { format!("{count} {unit}s") }
+}
+
+/// Apply a duration to an epoch and report what happened along the way.
+pub fn explain_duration(epoch: i64, duration: Duration) -> Result<ApplyReport> {
+    let (after, clamped) = apply_duration_reported(epoch, duration)?;
+    Ok(ApplyReport { before: epoch, after, duration, clamped })
+}
+
+/// Words that stand for the epoch origin, 1970-01-01T00:00:00Z.
+const EPOCH_KEYWORDS: [&str; 3] = ["epoch", "unix", "zero"];
+
+/// Whether `s` is a keyword for epoch 0 (`epoch`, `unix`, or `zero`, in any
+/// case). Every epoch and ISO-8601 parser accepts these.
+pub fn is_epoch_keyword(s: &str) -> bool {
+    EPOCH_KEYWORDS.iter().any(|keyword| s.trim().eq_ignore_ascii_case(keyword))
+}
+
 /// Parse an epoch timestamp from a string.
 pub fn parse_epoch(s: &str) -> Result<i64> {
+    Timestamp::parse_epoch(s, Precision::Seconds)?
+        .as_seconds()
+        .map_err(|_| EtError::InvalidEpoch(s.trim().to_string()))
+}
+
+/// Check whether an ISO-8601 string carries a timezone designator
+/// (`Z` or a numeric offset).
+fn has_timezone(s: &str) -> bool {
+    if s.contains('Z')
+        || s.contains('+')
+        || s.chars().enumerate().any(|(i, c)| {
+            c == '-' && i > 10
+        })
+    {
+        return true;
+    }
+
+    if let Some(t_pos) = s.find('T') {
+        let after_t = &s[t_pos..];
+        after_t.contains('Z') || after_t.contains('+') || after_t[1..].contains('-')
+    } else {
+        s.contains('Z')
+    }
+}
+
+/// Parse an ISO-8601 timestamp that must carry a timezone, keeping its
+/// offset and full sub-second precision.
+pub fn parse_iso_dt(s: &str) -> Result<OffsetDateTime> {
     let s = s.trim();
-    s.parse::<i64>()
-        .map_err(|_| EtError::InvalidEpoch(s.to_string()))
+    if is_epoch_keyword(s) {
+        return Ok(OffsetDateTime::UNIX_EPOCH);
+    }
+
+    // Check for timezone indicator
+    if !has_timezone(s) {
+        return Err(EtError::MissingTimezone(s.to_string()));
+    }
+
+    OffsetDateTime::parse(s, &Iso8601::PARSING)
+        .map_err(|_| EtError::InvalidIso(s.to_string()))
 }
 
 /// Parse an ISO-8601 timestamp to Unix epoch seconds.
+///
+/// Fractional seconds are accepted and truncated towards the earlier second.
 pub fn parse_iso(s: &str) -> Result<i64> {
+    Ok(parse_iso_dt(s)?.unix_timestamp())
+}
+
+/// Check that `s` has the strict RFC 3339 shape: `YYYY-MM-DDTHH:MM:SS`,
+/// optional fractional seconds, and then `Z` or a `±HH:MM` offset, which
+/// is required. `parse_iso` on its own also accepts `+0200` offsets, no
+/// timezone, omitted seconds, and basic forms such as `20240110T120000Z`.
+/// Epoch keywords pass.
+pub fn check_strict_iso(s: &str) -> Result<()> {
     let s = s.trim();
+    if is_epoch_keyword(s) {
+        return Ok(());
+    }
 
-    // Check for timezone indicator
-    if !s.contains('Z')
-        && !s.contains('+')
-        && !s.chars().enumerate().any(|(i, c)| {
-            c == '-' && i > 10
-        })
-    {
-        let has_tz = if let Some(t_pos) = s.find('T') {
-            let after_t = &s[t_pos..];
-            after_t.contains('Z') || after_t.contains('+') || after_t[1..].contains('-')
-        } else {
-            s.contains('Z')
-        };
+    let invalid = || {
+        EtError::InvalidIso(format!("{s} (strict mode needs YYYY-MM-DDTHH:MM:SS and Z or a ±HH:MM offset)"))
+    };
+    // `9` in a pattern stands for any digit
+    let shaped = |text: &str, pattern: &str| {
+        text.len() == pattern.len()
+            && text.bytes().zip(pattern.bytes()).all(|(c, p)| if p == b'9' { c.is_ascii_digit() } else { c == p })
+    };
 
-        if !has_tz {
-            return Err(EtError::MissingTimezone(s.to_string()));
+    let (datetime, rest) = s.split_at_checked(19).ok_or_else(invalid)?;
+    if !shaped(datetime, "9999-99-99T99:99:99") {
+        return Err(invalid());
+    }
+    let zone = match rest.strip_prefix('.') {
+        Some(fraction) => {
+            let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+            if digits == 0 {
+                return Err(invalid());
+            }
+            &fraction[digits..]
         }
+        None => rest,
+    };
+
+    match zone.as_bytes() {
+        [b'Z'] => Ok(()),
+        [b'+' | b'-', ..] if shaped(&zone[1..], "99:99") => Ok(()),
+        _ => Err(invalid()),
     }
+}
 
-    let dt = OffsetDateTime::parse(s, &Iso8601::PARSING)
-        .map_err(|_| EtError::InvalidIso(s.to_string()))?;
+/// Parse an ISO-8601 timestamp to Unix epoch seconds, first requiring the
+/// strict shape that `check_strict_iso` describes.
+pub fn parse_iso_strict(s: &str) -> Result<i64> {
+    check_strict_iso(s)?;
+    parse_iso(s)
+}
 
-    Ok(dt.unix_timestamp())
+/// Tidy loosely written ISO-8601 for lenient parsing: uppercase a lowercase
+/// `t` date/time separator and a trailing `z` UTC designator, so
+/// `2024-01-10t12:00:00,5z` becomes `2024-01-10T12:00:00,5Z`. Nothing else
+/// changes: a comma before fractional seconds needs no tidying, since every
+/// ISO-8601 parser accepts it, and numeric offsets such as `+05:30` are
+/// untouched.
+pub fn normalize_iso(s: &str) -> String {
+    let s = s.trim();
+    let bytes = s.as_bytes();
+    let digit_at = |i: usize| bytes.get(i).is_some_and(u8::is_ascii_digit);
+
+    let mut seen_separator = false;
+    s.char_indices()
+        .map(|(i, c)| match c {
+            't' if !seen_separator && i > 0 && digit_at(i - 1) && digit_at(i + 1) => {
+                seen_separator = true;
+                'T'
+            }
+            'z' if i + 1 == s.len() && i > 0 && digit_at(i - 1) => 'Z',
+            'T' => {
+                seen_separator = true;
+                c
+            }
+            _ => c,
+        })
+        .collect()
 }
 
-/// Format an epoch timestamp to ISO-8601 UTC.
-pub fn format_iso(epoch: i64) -> Result<String> {
-    let dt = OffsetDateTime::from_unix_timestamp(epoch)
-        .map_err(|_| EtError::InvalidEpoch(epoch.to_string()))?;
+/// Parse an ISO-8601 timestamp like `parse_iso`, but also accept lowercase
+/// `t` and `z` (see `normalize_iso`).
+pub fn parse_iso_lenient(s: &str) -> Result<i64> {
+    parse_iso(&normalize_iso(s))
+}
 
-    let format = time::format_description::parse(
-        "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero]Z",
-    )
-    .expect("valid format description");
+/// Parse an ISO-8601 timestamp to Unix epoch seconds, rounding fractional
+/// seconds to the nearest second (halves round up) instead of truncating.
+pub fn parse_iso_rounded(s: &str) -> Result<i64> {
+    Timestamp::parse_iso(s, Precision::Nanos)?
+        .round_to(Precision::Seconds)?
+        .as_seconds()
+}
 
-    dt.format(&format)
-        .map_err(|_| EtError::InvalidEpoch(epoch.to_string()))
+/// Parse an ISO-8601 timestamp to Unix epoch nanoseconds, keeping any
+/// fractional seconds.
+pub fn parse_iso_nanos(s: &str) -> Result<i128> {
+    Ok(parse_iso_dt(s)?.unix_timestamp_nanos())
 }
 
-/// Check if a string looks like a duration.
-pub fn is_duration(s: &str) -> bool {
+/// Parse an ISO-8601 timestamp to Unix epoch milliseconds. Digits beyond
+/// millisecond precision are truncated towards the earlier millisecond.
+pub fn parse_iso_millis(s: &str) -> Result<i64> {
+    let millis = Timestamp::parse_iso(s, Precision::Millis)?.value;
+    i64::try_from(millis).map_err(|_| EtError::Overflow)
+}
+
+/// Parse an ISO-8601 timestamp, interpreting a timestamp without a timezone
+/// as local time at `offset`. Timestamps that carry their own timezone are
+/// parsed exactly as `parse_iso` would.
+pub fn parse_iso_assuming(s: &str, offset: UtcOffset) -> Result<i64> {
+    Ok(parse_iso_dt_assuming(s, offset)?.unix_timestamp())
+}
+
+fn parse_iso_dt_assuming(s: &str, offset: UtcOffset) -> Result<OffsetDateTime> {
     let s = s.trim();
-    if s.is_empty() {
-        return false;
+    if has_timezone(s) || is_epoch_keyword(s) {
+        return parse_iso_dt(s);
     }
 
-    let first = s.chars().next().unwrap();
-    if first == '+' || first == '-' {
-        return true;
+    let dt = PrimitiveDateTime::parse(s, &Iso8601::PARSING)
+        .map_err(|_| EtError::InvalidIso(s.to_string()))?;
+
+    Ok(dt.assume_offset(offset))
+}
+
+/// Parse a UTC offset such as `+05:30`, `-0800`, `+02`, `+05:30:15`, `Z`,
+/// or `UTC`. Every flag and config key that takes an offset goes through
+/// here, so hours must be 0-23 and minutes and seconds 0-59 everywhere.
+pub fn parse_offset(s: &str) -> Result<UtcOffset> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("z") || s.eq_ignore_ascii_case("utc") {
+        return Ok(UtcOffset::UTC);
     }
 
-    // Check if it's digits followed by a unit letter
-    if first.is_ascii_digit() {
-        let last = s.chars().last().unwrap();
-        // Include M and Y for months/years
-        matches!(
-            last,
-            's' | 'm' | 'h' | 'd' | 'w' | 'S' | 'H' | 'D' | 'W' | 'M' | 'Y' | 'y'
-        )
+    let invalid = || EtError::InvalidOffset(s.to_string());
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => return Err(invalid()),
+    };
+    if !rest.chars().all(|c| c.is_ascii_digit() || c == ':') {
+        return Err(invalid());
+    }
+
+    // `HH:MM[:SS]`, or the compact `HH[MM[SS]]`
+    let fields: Vec<&str> = if rest.contains(':') {
+        rest.split(':').collect()
     } else {
-        false
+        (0..rest.len()).step_by(2).map(|i| &rest[i..rest.len().min(i + 2)]).collect()
+    };
+    if !(1..=3).contains(&fields.len())
+        || (rest.contains(':') && fields.len() == 1)
+        || fields.iter().any(|f| f.len() != 2)
+    {
+        return Err(invalid());
+    }
+
+    let mut parts = [0i8; 3];
+    for (part, field) in parts.iter_mut().zip(&fields) {
+        *part = field.parse().map_err(|_| invalid())?;
     }
+    let [hours, minutes, seconds] = parts;
+    if hours > 23 {
+        return Err(EtError::InvalidOffset(format!("{s}: hours must be below 24")));
+    }
+    if minutes > 59 || seconds > 59 {
+        return Err(EtError::InvalidOffset(format!("{s}: minutes and seconds must be below 60")));
+    }
+
+    UtcOffset::from_hms(sign * hours, sign * minutes, sign * seconds).map_err(|_| invalid())
+}
+
+/// Format an epoch timestamp as ISO-8601 wall-clock time at `offset`, e.g.
+/// `2024-07-01T05:00:00-05:00`.
+pub fn format_iso_at(epoch: i64, offset: UtcOffset) -> Result<String> {
+    let dt = utc_datetime(epoch)?
+        .to_offset(offset);
+
+    dt.format(ISO_OFFSET_FORMAT)
+        .map(|text| with_offset_seconds(text, offset, ":"))
+        .map_err(|_| EtError::InvalidEpoch(epoch.to_string()))
+}
+
+/// The offset layouts stop at minutes; append the seconds of an offset such
+/// as `+05:30:15` so the rendered offset matches the wall-clock time.
+fn with_offset_seconds(mut text: String, offset: UtcOffset, separator: &str) -> String {
+    let seconds = offset.seconds_past_minute().unsigned_abs();
+    if seconds != 0 {
+        text.push_str(&format!("{separator}{seconds:02}"));
+    }
+    text
+}
+
+/// Get the system's current UTC offset.
+pub fn local_offset() -> Result<UtcOffset> {
+    UtcOffset::current_local_offset().map_err(|_| EtError::IndeterminateOffset)
+}
+
+/// Format a UTC offset as `±HH:MM`, e.g. `-05:00` or `+00:00`, with `:SS`
+/// appended when the offset has seconds.
+pub fn format_offset(offset: UtcOffset) -> String {
+    let (hours, minutes, _) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    let text = format!("{sign}{:02}:{:02}", hours.unsigned_abs(), minutes.unsigned_abs());
+    with_offset_seconds(text, offset, ":")
+}
+
+/// ISO-8601 UTC layouts used for epoch output, one per precision, e.g.
+/// `2024-01-10T12:00:00Z` and `2024-01-10T12:00:00.123Z`.
+const ISO_FORMAT: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero]Z"
+);
+const ISO_FORMAT_MILLIS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:3]Z"
+);
+const ISO_FORMAT_MICROS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:6]Z"
+);
+const ISO_FORMAT_NANOS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:9]Z"
+);
+
+/// ISO-8601 with a numeric UTC offset, one per precision, e.g.
+/// `2024-07-01T05:00:00-05:00` and `2024-07-01T05:00:00.123-05:00`.
+const ISO_OFFSET_FORMAT: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero][offset_hour sign:mandatory]:[offset_minute]"
+);
+const ISO_OFFSET_FORMAT_MILLIS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:3][offset_hour sign:mandatory]:[offset_minute]"
+);
+const ISO_OFFSET_FORMAT_MICROS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:6][offset_hour sign:mandatory]:[offset_minute]"
+);
+const ISO_OFFSET_FORMAT_NANOS: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year]-[month padding:zero]-[day padding:zero]T[hour padding:zero]:[minute padding:zero]:[second padding:zero].[subsecond digits:9][offset_hour sign:mandatory]:[offset_minute]"
+);
+
+/// Compact ISO-8601 without separators, e.g. `20240110T120000Z`.
+const ISO_BASIC_FORMAT: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year][month padding:zero][day padding:zero]T[hour padding:zero][minute padding:zero][second padding:zero]Z"
+);
+
+/// Compact ISO-8601 with a numeric UTC offset, e.g. `20240110T040000-0800`.
+const ISO_BASIC_OFFSET_FORMAT: &[BorrowedFormatItem<'static>] = format_description!(
+    "[year][month padding:zero][day padding:zero]T[hour padding:zero][minute padding:zero][second padding:zero][offset_hour sign:mandatory][offset_minute]"
+);
+
+/// Format an epoch timestamp to ISO-8601 UTC.
+pub fn format_iso(epoch: i64) -> Result<String> {
+    Timestamp::from_seconds(epoch).format_iso()
+}
+
+/// Format an epoch timestamp in UTC with a named style (`iso`, `rfc3339`,
+/// `rfc2822`, `iso-basic`, or `wom` for `week_of_month`) or a raw `time`
+/// format description such as `[year]/[month]/[day]`.
+pub fn format_with(epoch: i64, format: &str) -> Result<String> {
+    Timestamp::from_seconds(epoch).format_with(format)
+}
+
+/// Write an epoch timestamp in decimal to `w`.
+pub fn write_epoch<W: Write>(w: &mut W, epoch: i64) -> Result<()> {
+    write!(w, "{epoch}")?;
+    Ok(())
+}
+
+/// Write an epoch timestamp as ISO-8601 UTC to `w`.
+///
+/// Unlike `format_iso`, this does not allocate, so it suits hot loops that
+/// format many timestamps into one buffer.
+pub fn write_iso<W: Write>(w: &mut W, epoch: i64) -> Result<()> {
+    Timestamp::from_seconds(epoch).write_iso(w)
+}
+
+/// Format an epoch timestamp in milliseconds to ISO-8601 UTC with three
+/// fractional digits (e.g. `2024-01-10T12:00:00.123Z`).
+pub fn format_iso_millis(millis: i64) -> Result<String> {
+    Timestamp::new(millis as i128, Precision::Millis).format_iso()
+}
+
+// Precision
+
+/// Resolution of an epoch value: how many ticks make up one second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    #[default]
+    Seconds,
+    Millis,
+    Micros,
+    Nanos,
+}
+
+impl Precision {
+    /// Number of ticks per second at this precision.
+    pub const fn per_second(self) -> i128 {
+        match self {
+            Precision::Seconds => 1,
+            Precision::Millis => 1_000,
+            Precision::Micros => 1_000_000,
+            Precision::Nanos => 1_000_000_000,
+        }
+    }
+
+    /// Number of fractional-second digits shown in ISO-8601 output.
+    pub const fn digits(self) -> usize {
+        match self {
+            Precision::Seconds => 0,
+            Precision::Millis => 3,
+            Precision::Micros => 6,
+            Precision::Nanos => 9,
+        }
+    }
+
+    /// Guess the precision of an integer epoch from its digit count, for
+    /// streams that mix units. Present-day epochs have 10 digits in seconds,
+    /// 13 in milliseconds, 16 in microseconds, and 19 in nanoseconds; each
+    /// guess also covers one digit either side. The sign and a unit suffix
+    /// are not counted.
+    pub fn guess(epoch: &str) -> Precision {
+        let digits = epoch.trim().bytes().filter(u8::is_ascii_digit).count();
+        match digits {
+            ..=11 => Precision::Seconds,
+            12..=14 => Precision::Millis,
+            15..=17 => Precision::Micros,
+            _ => Precision::Nanos,
+        }
+    }
+
+    fn iso_format(self) -> &'static [BorrowedFormatItem<'static>] {
+        match self {
+            Precision::Seconds => ISO_FORMAT,
+            Precision::Millis => ISO_FORMAT_MILLIS,
+            Precision::Micros => ISO_FORMAT_MICROS,
+            Precision::Nanos => ISO_FORMAT_NANOS,
+        }
+    }
+
+    fn iso_offset_format(self) -> &'static [BorrowedFormatItem<'static>] {
+        match self {
+            Precision::Seconds => ISO_OFFSET_FORMAT,
+            Precision::Millis => ISO_OFFSET_FORMAT_MILLIS,
+            Precision::Micros => ISO_OFFSET_FORMAT_MICROS,
+            Precision::Nanos => ISO_OFFSET_FORMAT_NANOS,
+        }
+    }
+}
+
+impl FromStr for Precision {
+    type Err = EtError;
+
+    /// Parse a precision name: `s`, `ms`, `us`, or `ns` (or `seconds`,
+    /// `millis`, `micros`, `nanos`).
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "s" | "seconds" => Ok(Precision::Seconds),
+            "ms" | "millis" => Ok(Precision::Millis),
+            "us" | "micros" => Ok(Precision::Micros),
+            "ns" | "nanos" => Ok(Precision::Nanos),
+            other => Err(EtError::UnsupportedPrecision(other.to_string())),
+        }
+    }
+}
+
+/// An epoch value together with the precision it is counted in, e.g.
+/// `1704888000123` at `Precision::Millis`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Timestamp {
+    pub value: i128,
+    pub precision: Precision,
+}
+
+impl Timestamp {
+    pub const fn new(value: i128, precision: Precision) -> Self {
+        Timestamp { value, precision }
+    }
+
+    /// A timestamp in whole epoch seconds.
+    pub const fn from_seconds(epoch: i64) -> Self {
+        Timestamp::new(epoch as i128, Precision::Seconds)
+    }
+
+    /// A timestamp in epoch nanoseconds.
+    pub const fn from_nanos(nanos: i128) -> Self {
+        Timestamp::new(nanos, Precision::Nanos)
+    }
+
+    /// Parse an integer epoch counted at `precision`, or an epoch keyword
+    /// (see `is_epoch_keyword`).
+    ///
+    /// A unit suffix (`s`, `ms`, `us`, or `ns`) says what the number counts
+    /// instead, and the result is converted to `precision`: at seconds,
+    /// `1704912345000ms` is `1704912345`.
+    pub fn parse_epoch(s: &str, precision: Precision) -> Result<Self> {
+        let s = s.trim();
+        if is_epoch_keyword(s) {
+            return Ok(Timestamp::new(0, precision));
+        }
+
+        let suffixed = [
+            ("ms", Precision::Millis),
+            ("us", Precision::Micros),
+            ("ns", Precision::Nanos),
+            ("s", Precision::Seconds),
+        ]
+        .into_iter()
+        .find_map(|(suffix, unit)| Some((s.strip_suffix(suffix)?, unit)));
+        let (number, unit) = suffixed.unwrap_or((s, precision));
+
+        let value = number.parse::<i128>()
+            .map_err(|_| EtError::InvalidEpoch(s.to_string()))?;
+        Timestamp::new(value, unit).to_precision(precision)
+    }
+
+    /// Parse either an integer epoch counted at `precision` or an ISO-8601
+    /// timestamp, as `parse_epoch` and `parse_iso` do.
+    pub fn parse(s: &str, precision: Precision) -> Result<Self> {
+        Timestamp::parse_epoch(s, precision).or_else(|_| Timestamp::parse_iso(s, precision))
+    }
+
+    /// Parse an ISO-8601 timestamp, keeping fractional seconds down to
+    /// `precision` and truncating anything finer towards the earlier tick.
+    pub fn parse_iso(s: &str, precision: Precision) -> Result<Self> {
+        Timestamp::from_nanos(parse_iso_nanos(s)?).to_precision(precision)
+    }
+
+    /// Like `parse_iso`, but a timestamp without a timezone is read as local
+    /// time at `offset`.
+    pub fn parse_iso_assuming(s: &str, offset: UtcOffset, precision: Precision) -> Result<Self> {
+        let nanos = parse_iso_dt_assuming(s, offset)?.unix_timestamp_nanos();
+        Timestamp::from_nanos(nanos).to_precision(precision)
+    }
+
+    /// The same instant in epoch nanoseconds.
+    pub fn as_nanos(self) -> Result<i128> {
+        let factor = Precision::Nanos.per_second() / self.precision.per_second();
+        self.value.checked_mul(factor).ok_or(EtError::Overflow)
+    }
+
+    /// The same instant in whole epoch seconds, truncated towards the earlier
+    /// second.
+    pub fn as_seconds(self) -> Result<i64> {
+        let seconds = self.value.div_euclid(self.precision.per_second());
+        i64::try_from(seconds).map_err(|_| EtError::Overflow)
+    }
+
+    /// Convert to another precision. Converting to a coarser precision
+    /// truncates towards the earlier tick.
+    pub fn to_precision(self, precision: Precision) -> Result<Self> {
+        let (from, to) = (self.precision.per_second(), precision.per_second());
+        let value = if to >= from {
+            self.value.checked_mul(to / from).ok_or(EtError::Overflow)?
+        } else {
+            self.value.div_euclid(from / to)
+        };
+        Ok(Timestamp::new(value, precision))
+    }
+
+    /// Convert to another precision, rounding to the nearest tick rather
+    /// than truncating. Halves round towards the later tick.
+    pub fn round_to(self, precision: Precision) -> Result<Self> {
+        let (from, to) = (self.precision.per_second(), precision.per_second());
+        if to >= from {
+            return self.to_precision(precision);
+        }
+        let factor = from / to;
+        let value = self.value
+            .checked_add(factor / 2)
+            .ok_or(EtError::Overflow)?
+            .div_euclid(factor);
+        Ok(Timestamp::new(value, precision))
+    }
+
+    fn datetime(self) -> Result<OffsetDateTime> {
+        let seconds = self.as_seconds().map_err(|_| EtError::InvalidEpoch(self.value.to_string()))?;
+        utc_datetime(seconds)?;
+        self.as_nanos()
+            .and_then(|nanos| {
+                OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| EtError::Overflow)
+            })
+            .map_err(|_| EtError::InvalidEpoch(self.value.to_string()))
+    }
+
+    /// Format as ISO-8601 UTC with as many fractional digits as the
+    /// precision carries.
+    pub fn format_iso(self) -> Result<String> {
+        self.datetime()?
+            .format(self.precision.iso_format())
+            .map_err(|_| EtError::InvalidEpoch(self.value.to_string()))
+    }
+
+    /// Format in UTC with a named style or raw `time` format description,
+    /// as `format_with` does.
+    pub fn format_with(self, format: &str) -> Result<String> {
+        self.format_with_at(format, UtcOffset::UTC)
+    }
+
+    /// Like `format_with`, but render the wall-clock time at `offset`. The
+    /// `iso` and `iso-basic` styles end in the offset instead of `Z` unless
+    /// `offset` is UTC.
+    pub fn format_with_at(self, format: &str, offset: UtcOffset) -> Result<String> {
+        let dt = self.datetime()?.to_offset(offset);
+        let unrepresentable =
+            |_| EtError::InvalidFormat(format!("{format} cannot represent {}", self.value));
+
+        match format.trim() {
+            "iso" if offset.is_utc() => self.format_iso(),
+            "iso" => dt
+                .format(self.precision.iso_offset_format())
+                .map(|text| with_offset_seconds(text, offset, ":"))
+                .map_err(unrepresentable),
+            "rfc3339" => dt.format(&Rfc3339).map_err(unrepresentable),
+            "rfc2822" => dt.format(&Rfc2822).map_err(unrepresentable),
+            "iso-basic" if offset.is_utc() => dt.format(ISO_BASIC_FORMAT).map_err(unrepresentable),
+            "iso-basic" => dt
+                .format(ISO_BASIC_OFFSET_FORMAT)
+                .map(|text| with_offset_seconds(text, offset, ""))
+                .map_err(unrepresentable),
+            "wom" => Ok(weekday_occurrence(dt.day()).to_string()),
+            pattern => {
+                let items = time::format_description::parse(pattern)
+                    .map_err(|_| EtError::InvalidFormat(pattern.to_string()))?;
+                dt.format(&items).map_err(unrepresentable)
+            }
+        }
+    }
+
+    /// Write as ISO-8601 UTC to `w`, like `format_iso` but without allocating.
+    pub fn write_iso<W: Write>(self, w: &mut W) -> Result<()> {
+        match self.datetime()?.format_into(w, self.precision.iso_format()) {
+            Ok(_) => Ok(()),
+            Err(time::error::Format::StdIo(e)) => Err(EtError::Io(e)),
+            Err(_) => Err(EtError::InvalidEpoch(self.value.to_string())),
+        }
+    }
+
+    /// Apply a duration, keeping the sub-second part unchanged.
+    pub fn apply(self, duration: Duration) -> Result<Self> {
+        self.with_seconds(apply_duration(self.as_seconds()?, duration)?)
+    }
+
+    /// Apply a duration like `apply`, but clamp the whole seconds to the
+    /// `i64` range instead of failing (see `apply_duration_saturating`).
+    pub fn apply_saturating(self, duration: Duration) -> Result<Self> {
+        self.with_seconds(apply_duration_saturating(self.as_seconds()?, duration))
+    }
+
+    /// This timestamp moved to `seconds`, keeping its sub-second part.
+    fn with_seconds(self, seconds: i64) -> Result<Self> {
+        let per_second = self.precision.per_second();
+        let fraction = self.value.rem_euclid(per_second);
+        let value = (seconds as i128)
+            .checked_mul(per_second)
+            .and_then(|v| v.checked_add(fraction))
+            .ok_or(EtError::Overflow)?;
+        Ok(Timestamp::new(value, self.precision))
+    }
+}
+
+/// Epoch seconds or ISO-8601, read at whole-second precision.
+impl TryFrom<&str> for Timestamp {
+    type Error = EtError;
+
+    fn try_from(s: &str) -> Result<Self> {
+        Timestamp::parse(s, Precision::Seconds)
+    }
+}
+
+impl TryFrom<String> for Timestamp {
+    type Error = EtError;
+
+    fn try_from(s: String) -> Result<Self> {
+        Timestamp::try_from(s.as_str())
+    }
+}
+
+impl From<i64> for Timestamp {
+    fn from(epoch: i64) -> Self {
+        Timestamp::from_seconds(epoch)
+    }
+}
+
+/// Whole epoch seconds, truncated like `as_seconds`.
+impl TryFrom<Timestamp> for i64 {
+    type Error = EtError;
+
+    fn try_from(ts: Timestamp) -> Result<Self> {
+        ts.as_seconds()
+    }
+}
+
+impl From<OffsetDateTime> for Timestamp {
+    fn from(dt: OffsetDateTime) -> Self {
+        Timestamp::from_nanos(dt.unix_timestamp_nanos())
+    }
+}
+
+/// The instant in UTC.
+impl TryFrom<Timestamp> for OffsetDateTime {
+    type Error = EtError;
+
+    fn try_from(ts: Timestamp) -> Result<Self> {
+        ts.datetime()
+    }
+}
+
+/// ISO-8601 UTC, as `format_iso`. A value outside the range `time` can
+/// represent is written as the bare epoch instead.
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.format_iso() {
+            Ok(iso) => f.write_str(&iso),
+            Err(_) => write!(f, "{}", self.value),
+        }
+    }
+}
+
+/// Parse a timestamp given either as epoch seconds or as ISO-8601.
+pub fn parse_timestamp(s: &str) -> Result<i64> {
+    parse_epoch(s).or_else(|_| parse_iso(s))
+}
+
+/// Parse a timestamp given either as epoch seconds or as ISO-8601, reading
+/// an ISO-8601 timestamp without a timezone as local time at `offset`.
+pub fn parse_timestamp_assuming(s: &str, offset: UtcOffset) -> Result<i64> {
+    parse_epoch(s).or_else(|_| parse_iso_assuming(s, offset))
+}
+
+/// A way of writing a timestamp that `parse_any` recognizes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Epoch seconds, e.g. `1704888000`
+    Epoch,
+    /// ISO-8601 with a timezone, e.g. `2024-01-10T12:00:00Z`
+    Iso,
+    /// RFC 2822, e.g. `Wed, 10 Jan 2024 12:00:00 +0000`
+    Rfc2822,
+    /// RFC 3339, also with a space between date and time, e.g.
+    /// `2024-01-10 12:00:00Z`
+    Rfc3339,
+    /// A bare date, read as midnight UTC, e.g. `2024-01-10`
+    Date,
+}
+
+impl InputFormat {
+    /// Every format, in the order `parse_any` tries them.
+    pub const ALL: [InputFormat; 5] = [
+        InputFormat::Epoch,
+        InputFormat::Iso,
+        InputFormat::Rfc2822,
+        InputFormat::Rfc3339,
+        InputFormat::Date,
+    ];
+
+    /// Parse `s` in this format to Unix epoch seconds.
+    pub fn parse(self, s: &str) -> Result<i64> {
+        match self {
+            InputFormat::Epoch => parse_epoch(s),
+            format => Ok(format.datetime(s)?.unix_timestamp()),
+        }
+    }
+
+    /// Like `parse`, but keep fractional seconds down to `precision`. An
+    /// epoch counts in `precision` units, as `Timestamp::parse_epoch` reads
+    /// it.
+    pub fn parse_timestamp(self, s: &str, precision: Precision) -> Result<Timestamp> {
+        match self {
+            InputFormat::Epoch => Timestamp::parse_epoch(s, precision),
+            format => {
+                let nanos = format.datetime(s)?.unix_timestamp_nanos();
+                Timestamp::from_nanos(nanos).to_precision(precision)
+            }
+        }
+    }
+
+    /// Parse `s` in a date format, at full precision.
+    fn datetime(self, s: &str) -> Result<OffsetDateTime> {
+        let s = s.trim();
+        let invalid = |_| EtError::InvalidIso(s.to_string());
+        match self {
+            InputFormat::Epoch => utc_datetime(parse_epoch(s)?),
+            InputFormat::Iso => parse_iso_dt(s),
+            InputFormat::Rfc2822 => OffsetDateTime::parse(s, &Rfc2822).map_err(invalid),
+            InputFormat::Rfc3339 => {
+                // RFC 3339 allows a lowercase `t` and `z`, but like ISO-8601
+                // input they need `normalize_iso` first
+                if s.contains(['t', 'z']) {
+                    return Err(EtError::InvalidIso(s.to_string()));
+                }
+                let s = match s.split_once(' ') {
+                    Some((date, time)) => format!("{date}T{time}"),
+                    None => s.to_string(),
+                };
+                OffsetDateTime::parse(&s, &Rfc3339).map_err(invalid)
+            }
+            InputFormat::Date => {
+                let date = Date::parse(s, DATE_FORMAT)
+                    .map_err(|_| EtError::InvalidDate(format!("{s} is not YYYY-MM-DD")))?;
+                Ok(date.midnight().assume_offset(UtcOffset::UTC))
+            }
+        }
+    }
+}
+
+impl FromStr for InputFormat {
+    type Err = EtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "epoch" => Ok(InputFormat::Epoch),
+            "iso" => Ok(InputFormat::Iso),
+            "rfc2822" => Ok(InputFormat::Rfc2822),
+            "rfc3339" => Ok(InputFormat::Rfc3339),
+            "date" => Ok(InputFormat::Date),
+            other => Err(EtError::InvalidFormat(format!(
+                "{other} (expected epoch, iso, rfc2822, rfc3339, or date)"
+            ))),
+        }
+    }
+}
+
+const DATE_FORMAT: &[BorrowedFormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Parse a timestamp written in any `InputFormat`, trying epoch, ISO-8601,
+/// RFC 2822, RFC 3339 with a space separator, and a bare date in that
+/// order and returning the first that fits.
+///
+/// An ISO-8601 timestamp that is only missing its timezone reports that
+/// rather than the list of formats tried.
+pub fn parse_any(s: &str) -> Result<i64> {
+    parse_any_timestamp(s, Precision::Seconds)?.as_seconds()
+}
+
+/// Like `parse_any`, but keep fractional seconds down to `precision`. An
+/// epoch counts in `precision` units.
+pub fn parse_any_timestamp(s: &str, precision: Precision) -> Result<Timestamp> {
+    let found = InputFormat::ALL.iter().find_map(|format| format.parse_timestamp(s, precision).ok());
+    if let Some(timestamp) = found {
+        return Ok(timestamp);
+    }
+    let s = s.trim();
+    if parse_iso_assuming(s, UtcOffset::UTC).is_ok() {
+        return Err(EtError::MissingTimezone(s.to_string()));
+    }
+    Err(EtError::UnrecognizedTimestamp(s.to_string()))
+}
+
+/// Parse `s` with a named `InputFormat` (`epoch`, `iso`, `rfc2822`,
+/// `rfc3339`, or `date`) or a raw `time` format description such as
+/// `[month]/[day]/[year]`, the reverse of `format_with`. A description
+/// without an offset is read as UTC, and one without a time of day as
+/// midnight.
+pub fn parse_with(s: &str, format: &str) -> Result<i64> {
+    if let Ok(named) = format.parse::<InputFormat>() {
+        return named.parse(s);
+    }
+    let s = s.trim();
+    let items = time::format_description::parse(format)
+        .map_err(|_| EtError::InvalidFormat(format.to_string()))?;
+    let mismatch = || EtError::InvalidDate(format!("{s} does not match {format}"));
+
+    let mut parsed = Parsed::new();
+    let rest = parsed.parse_items(s.as_bytes(), &items).map_err(|_| mismatch())?;
+    if !rest.is_empty() {
+        return Err(mismatch());
+    }
+    let dt = OffsetDateTime::try_from(parsed)
+        .or_else(|_| PrimitiveDateTime::try_from(parsed).map(PrimitiveDateTime::assume_utc))
+        .or_else(|_| Date::try_from(parsed).map(|date| date.midnight().assume_utc()))
+        .map_err(|_| mismatch())?;
+    Ok(dt.unix_timestamp())
+}
+
+/// Seconds elapsed from `epoch` to `now`. Negative if `epoch` is in the future.
+pub fn since(epoch: i64, now: i64) -> Result<i64> {
+    now.checked_sub(epoch).ok_or(EtError::Overflow)
+}
+
+/// Seconds remaining from `now` until `epoch`. Negative if `epoch` has passed.
+pub fn until(epoch: i64, now: i64) -> Result<i64> {
+    epoch.checked_sub(now).ok_or(EtError::Overflow)
+}
+
+/// Units used when describing a span of time, largest first, with their
+/// abbreviations.
+const HUMAN_UNITS: [(&str, &str, i128); 7] = [
+    ("year", "y", 365 * 86400),
+    ("month", "mo", 30 * 86400),
+    ("week", "w", 7 * 86400),
+    ("day", "d", 86400),
+    ("hour", "h", 3600),
+    ("minute", "m", 60),
+    ("second", "s", 1),
+];
+
+/// Describe `epoch` relative to `now` using its largest whole unit,
+/// e.g. "3 hours ago" or "in 2 days".
+///
+/// Months and years are approximated as 30 and 365 days.
+pub fn humanize_relative(epoch: i64, now: i64) -> String {
+    humanize_relative_parts(epoch, now, 1, false)
+}
+
+/// `humanize_relative` in compact form for dense displays, e.g. "3h ago",
+/// "in 2d", or "1mo ago".
+pub fn humanize_relative_abbrev(epoch: i64, now: i64) -> String {
+    humanize_relative_parts(epoch, now, 1, true)
+}
+
+/// Describe `epoch` relative to `now` using its `parts` largest non-zero
+/// units, e.g. "1 day 3 hours ago" for two parts, or "1d 3h ago" when
+/// `abbrev`. Each unit is counted whole and the rest carried to the next, so
+/// smaller units left out are dropped rather than rounded. Zero parts counts
+/// as one.
+pub fn humanize_relative_parts(epoch: i64, now: i64, parts: usize, abbrev: bool) -> String {
+    let delta = epoch as i128 - now as i128;
+    if delta == 0 {
+        return "now".to_string();
+    }
+
+    let mut remaining = delta.abs();
+    let mut words = Vec::new();
+    for (unit, short, size) in HUMAN_UNITS {
+        if words.len() == parts.max(1) {
+            break;
+        }
+        if remaining >= size {
+            let count = remaining / size;
+            remaining %= size;
+            let plural = if count == 1 { "" } else { "s" };
+            words.push(if abbrev { format!("{count}{short}") } else { format!("{count} {unit}{plural}") });
+        }
+    }
+    let text = words.join(" ");
+
+    if delta < 0 {
+        format!("{text} ago")
+    } else {
+        format!("in {text}")
+    }
+}
+
+/// Break a number of seconds into days, hours, minutes, and seconds, e.g.
+/// 90061 is "1d 1h 1m 1s". Zero components are left out; zero itself is
+/// "0s" and negative counts get a leading `-`.
+pub fn humanize_duration(seconds: i64) -> String {
+    if seconds == 0 {
+        return "0s".to_string();
+    }
+
+    let mut remaining = seconds.unsigned_abs();
+    let mut parts = Vec::new();
+    for (unit, size) in [("d", 86400), ("h", 3600), ("m", 60), ("s", 1)] {
+        if remaining >= size {
+            parts.push(format!("{}{unit}", remaining / size));
+            remaining %= size;
+        }
+    }
+
+    let sign = if seconds < 0 { "-" } else { "" };
+    format!("{sign}{}", parts.join(" "))
+}
+
+/// Write a number of seconds as an ISO 8601 duration, e.g. 5400 is
+/// `PT1H30M` and 90061 is `P1DT1H1M1S`. Days are the largest unit, zero
+/// components are left out, zero itself is `PT0S`, and negative counts get
+/// a leading `-`.
+pub fn format_iso8601_duration(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let mut remaining = seconds.unsigned_abs();
+    let days = remaining / 86400;
+    remaining %= 86400;
+
+    let mut time = String::new();
+    for (designator, size) in [('H', 3600), ('M', 60), ('S', 1)] {
+        if remaining >= size {
+            time.push_str(&format!("{}{designator}", remaining / size));
+            remaining %= size;
+        }
+    }
+
+    match (days, time.is_empty()) {
+        (0, true) => "PT0S".to_string(),
+        (0, false) => format!("{sign}PT{time}"),
+        (days, true) => format!("{sign}P{days}D"),
+        (days, false) => format!("{sign}P{days}DT{time}"),
+    }
+}
+
+// Color
+
+const RESET: &str = "\x1b[0m";
+const DATE_COLOR: &str = "\x1b[34m";
+const TIME_COLOR: &str = "\x1b[32m";
+const RELATIVE_COLOR: &str = "\x1b[33m";
+
+/// Color an ISO-8601 timestamp for a terminal: the date in blue and the time
+/// in green. Strings without a `T` separator are returned unchanged.
+pub fn color_iso(iso: &str) -> String {
+    match iso.split_once('T') {
+        Some((date, time)) => format!("{DATE_COLOR}{date}{RESET}T{TIME_COLOR}{time}{RESET}"),
+        None => iso.to_string(),
+    }
+}
+
+/// Color the "ago"/"in" words of a `humanize_relative` description.
+pub fn color_relative(text: &str) -> String {
+    if let Some(rest) = text.strip_suffix(" ago") {
+        format!("{rest} {RELATIVE_COLOR}ago{RESET}")
+    } else if let Some(rest) = text.strip_prefix("in ") {
+        format!("{RELATIVE_COLOR}in{RESET} {rest}")
+    } else {
+        format!("{RELATIVE_COLOR}{text}{RESET}")
+    }
+}
+
+/// A span between two timestamps, written `A..B` (end excluded) or `A..=B`
+/// (end included). The end may come before the start for descending ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpochRange {
+    pub start: i64,
+    pub end: i64,
+    pub inclusive: bool,
+}
+
+impl EpochRange {
+    /// Parse `A..B` or `A..=B`, where each endpoint is epoch seconds or ISO-8601.
+    pub fn parse(s: &str) -> Result<Self> {
+        EpochRange::parse_at(s, Precision::Seconds)
+    }
+
+    /// Like `parse`, but an epoch endpoint counts in `precision` units. Both
+    /// endpoints are truncated to whole seconds.
+    pub fn parse_at(s: &str, precision: Precision) -> Result<Self> {
+        let s = s.trim();
+        let (start, end, inclusive) = if let Some((start, end)) = s.split_once("..=") {
+            (start, end, true)
+        } else if let Some((start, end)) = s.split_once("..") {
+            (start, end, false)
+        } else {
+            return Err(EtError::InvalidRange(s.to_string()));
+        };
+
+        if start.trim().is_empty() || end.trim().is_empty() {
+            return Err(EtError::InvalidRange(s.to_string()));
+        }
+
+        let endpoint = |s| Timestamp::parse(s, precision)?.as_seconds();
+        Ok(EpochRange { start: endpoint(start)?, end: endpoint(end)?, inclusive })
+    }
+
+    /// Check whether `epoch` lies within the range.
+    pub fn contains(&self, epoch: i64) -> bool {
+        let (low, high) = if self.start <= self.end {
+            (self.start, self.end)
+        } else {
+            (self.end, self.start)
+        };
+
+        // The excluded endpoint is always `end`, whichever side it is on
+        (low..=high).contains(&epoch) && (self.inclusive || epoch != self.end)
+    }
+}
+
+/// Check whether a string uses the `A..B` range syntax.
+pub fn is_range(s: &str) -> bool {
+    s.contains("..")
+}
+
+/// Return `step` repeated `n` times.
+fn nth_step(step: Duration, n: i64) -> Result<Duration> {
+    match step {
+        Duration::Fixed { .. } => nth_step(step.resolved()?, n),
+        Duration::Seconds(secs) => secs
+            .checked_mul(n)
+            .map(Duration::Seconds)
+            .ok_or(EtError::Overflow),
+        Duration::Months(months) => i32::try_from(n)
+            .ok()
+            .and_then(|n| months.checked_mul(n))
+            .map(Duration::Months)
+            .ok_or(EtError::Overflow),
+        Duration::Years(years) => i32::try_from(n)
+            .ok()
+            .and_then(|n| years.checked_mul(n))
+            .map(Duration::Years)
+            .ok_or(EtError::Overflow),
+    }
+}
+
+/// Timestamps from the start of a range towards its end, one step apart.
+///
+/// Each value is computed from the start (`start + n * step`) rather than from
+/// the previous value, so calendar steps don't drift after a clamped day
+/// (Jan 31, Feb 29, Mar 31 rather than Jan 31, Feb 29, Mar 29).
+#[derive(Debug, Clone)]
+pub struct Sequence {
+    range: EpochRange,
+    step: Duration,
+    n: i64,
+    done: bool,
+}
+
+impl Iterator for Sequence {
+    type Item = Result<i64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let value = nth_step(self.step, self.n)
+            .and_then(|d| apply_duration(self.range.start, d));
+        match value {
+            Ok(epoch) if self.range.contains(epoch) => {
+                self.n += 1;
+                Some(Ok(epoch))
+            }
+            Ok(_) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Step through a range. The step must be non-zero and move from the start
+/// towards the end (negative for descending ranges).
+pub fn sequence(range: EpochRange, step: Duration) -> Result<Sequence> {
+    let direction = match step {
+        Duration::Seconds(secs) => secs.signum(),
+        Duration::Fixed { value, .. } => value.signum(),
+        Duration::Months(months) => months.signum() as i64,
+        Duration::Years(years) => years.signum() as i64,
+    };
+
+    if direction == 0 {
+        return Err(EtError::InvalidDuration("step must not be zero".to_string()));
+    }
+    let span = range.end as i128 - range.start as i128;
+    if span.signum() as i64 == -direction {
+        return Err(EtError::InvalidRange(
+            "step moves away from the end of the range".to_string(),
+        ));
+    }
+
+    Ok(Sequence { range, step, n: 0, done: false })
+}
+
+/// Count the timestamps `sequence` would produce without producing them.
+/// Fixed steps are counted arithmetically; calendar steps are walked, since
+/// clamping makes their length vary.
+pub fn sequence_len(range: EpochRange, step: Duration) -> Result<u64> {
+    let mut steps = sequence(range, step)?;
+    let Duration::Seconds(secs) = step.resolved()? else {
+        return steps.try_fold(0, |n, epoch| epoch.map(|_| n + 1));
+    };
+
+    let span = (range.end as i128 - range.start as i128).unsigned_abs();
+    let secs = secs.unsigned_abs() as u128;
+    let count = match (span, range.inclusive) {
+        (_, true) => span / secs + 1,
+        (0, false) => 0,
+        (_, false) => (span - 1) / secs + 1,
+    };
+    u64::try_from(count).map_err(|_| EtError::Overflow)
+}
+
+/// Count how many whole steps separate `from` and `to`, dropping a partial
+/// final step. Months and years step on the calendar from `from`, so
+/// 2024-01-15 to 2024-07-20 is 6 steps of `1M`. The count is negative when
+/// `to` is before `from`; the sign of `step` does not matter.
+pub fn count_steps(from: i64, to: i64, step: Duration) -> Result<i64> {
+    let step = if step.as_seconds_approx() < 0 { step.negate()? } else { step };
+    let size = step.as_seconds_approx();
+    if size == 0 {
+        return Err(EtError::InvalidDuration("step must not be zero".to_string()));
+    }
+
+    let (step, direction) = if to < from { (step.negate()?, -1) } else { (step, 1) };
+    let span = (to as i128 - from as i128).abs();
+    if step.is_fixed() {
+        let n = span / size as i128 * direction as i128;
+        return i64::try_from(n).map_err(|_| EtError::Overflow);
+    }
+
+    // Calendar steps need a real date to start from
+    decompose(from)?;
+    let within = |k: i64| {
+        nth_step(step, k)
+            .and_then(|d| apply_duration(from, d))
+            .is_ok_and(|epoch| if direction > 0 { epoch <= to } else { epoch >= to })
+    };
+
+    // Estimate from the average step size, then settle on the calendar
+    let mut n = (span / size as i128) as i64;
+    while n > 0 && !within(n) {
+        n -= 1;
+    }
+    while within(n + 1) {
+        n += 1;
+    }
+    Ok(n * direction)
+}
+
+/// An endless stream of pseudo-random epochs drawn uniformly from
+/// `from..to` (end excluded). The same seed always gives the same epochs.
+#[derive(Debug, Clone)]
+pub struct RandomEpochs {
+    start: i64,
+    span: u64,
+    state: u64,
+}
+
+impl RandomEpochs {
+    /// Draw from `from..to`. `from` must be before `to`.
+    pub fn new(from: i64, to: i64, seed: u64) -> Result<Self> {
+        if from >= to {
+            return Err(EtError::InvalidRange(format!("{from} must be before {to}")));
+        }
+        // The span of two i64 values always fits in a u64
+        let span = (to as i128 - from as i128) as u64;
+        Ok(RandomEpochs { start: from, span, state: seed })
+    }
+
+    /// SplitMix64: small, fast, and good enough for test data.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+impl Iterator for RandomEpochs {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        // Scale into the span with a multiply-shift rather than a modulo
+        let offset = ((self.next_u64() as u128 * self.span as u128) >> 64) as i128;
+        Some((self.start as i128 + offset) as i64)
+    }
+}
+
+// Periods
+
+/// A calendar period that an epoch can be snapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    /// Calendar quarters starting January, April, July, and October
+    Quarter,
+    Year,
+}
+
+impl FromStr for Period {
+    type Err = EtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "minute" => Ok(Period::Minute),
+            "hour" => Ok(Period::Hour),
+            "day" => Ok(Period::Day),
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            "quarter" => Ok(Period::Quarter),
+            "year" => Ok(Period::Year),
+            other => Err(EtError::UnsupportedPeriod(other.to_string())),
+        }
+    }
+}
+
+/// The first day of the week: Monday for ISO weeks, Sunday for US calendars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WeekStart {
+    #[default]
+    Monday,
+    Sunday,
+}
+
+impl FromStr for WeekStart {
+    type Err = EtError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "mon" | "monday" => Ok(WeekStart::Monday),
+            "sun" | "sunday" => Ok(WeekStart::Sunday),
+            other => Err(EtError::InvalidWeekStart(other.to_string())),
+        }
+    }
+}
+
+/// The first second of the period containing `epoch`, in UTC. Weeks begin
+/// on `week_start` at 00:00:00Z.
+pub fn start_of(epoch: i64, period: Period, week_start: WeekStart) -> Result<i64> {
+    let date = utc_datetime(epoch)?
+        .date();
+
+    let start = match period {
+        Period::Minute => return Ok(epoch - epoch.rem_euclid(60)),
+        Period::Hour => return Ok(epoch - epoch.rem_euclid(3600)),
+        Period::Day => return Ok(epoch - epoch.rem_euclid(86400)),
+        Period::Week => {
+            let days_in = match week_start {
+                WeekStart::Monday => date.weekday().number_days_from_monday(),
+                WeekStart::Sunday => date.weekday().number_days_from_sunday(),
+            };
+            date.checked_sub(time::Duration::days(days_in as i64))
+                .ok_or(EtError::Overflow)?
+        }
+        Period::Month => date.replace_day(1).map_err(|_| EtError::Overflow)?,
+        Period::Quarter => {
+            let first_month = (date.month() as u8 - 1) / 3 * 3 + 1;
+            let month = Month::try_from(first_month).map_err(|_| EtError::Overflow)?;
+            Date::from_calendar_date(date.year(), month, 1).map_err(|_| EtError::Overflow)?
+        }
+        Period::Year => Date::from_calendar_date(date.year(), Month::January, 1)
+            .map_err(|_| EtError::Overflow)?,
+    };
+
+    Ok(start.midnight().assume_utc().unix_timestamp())
+}
+
+/// The last second of the period containing `epoch`, in UTC, for an
+/// inclusive end (`<= end`). See `next_start_of` for an exclusive one.
+pub fn end_of(epoch: i64, period: Period, week_start: WeekStart) -> Result<i64> {
+    Ok(next_start_of(epoch, period, week_start)? - 1)
+}
+
+/// The first second of the period after the one containing `epoch`, in UTC:
+/// an exclusive end (`< end`), such as 00:00:00 on the first of the next
+/// month.
+pub fn next_start_of(epoch: i64, period: Period, week_start: WeekStart) -> Result<i64> {
+    let start = start_of(epoch, period, week_start)?;
+    let next = match period {
+        Period::Minute => start.checked_add(60),
+        Period::Hour => start.checked_add(3600),
+        Period::Day => start.checked_add(86400),
+        Period::Week => start.checked_add(7 * 86400),
+        Period::Month => apply_duration(start, Duration::Months(1)).ok(),
+        Period::Quarter => apply_duration(start, Duration::Months(3)).ok(),
+        Period::Year => apply_duration(start, Duration::Years(1)).ok(),
+    };
+    next.ok_or(EtError::Overflow)
+}
+
+/// The length of a fixed duration in seconds, for operations that only make
+/// sense with one. A month or a year has no fixed length, so a calendar
+/// duration is an `UnsupportedUnit` error that says so.
+pub fn require_fixed(duration: Duration) -> Result<i64> {
+    match duration.resolved()? {
+        Duration::Seconds(seconds) => Ok(seconds),
+        _ => Err(EtError::UnsupportedUnit(format!(
+            "{duration} (months and years have no fixed length; use s, m, h, d, or w)"
+        ))),
+    }
+}
+
+/// The size of a fixed, positive step for `floor_to` and `ceil_to`.
+fn multiple_of(step: Duration) -> Result<i64> {
+    match require_fixed(step)? {
+        secs if secs > 0 => Ok(secs),
+        _ => Err(EtError::InvalidDuration(
+            "rounding needs a positive fixed duration such as 15m".to_string(),
+        )),
+    }
+}
+
+/// Round `epoch` down to a multiple of `step` since the epoch, e.g. to the
+/// quarter hour with `15m`. Negative epochs round towards the past too.
+pub fn floor_to(epoch: i64, step: Duration) -> Result<i64> {
+    let step = multiple_of(step)?;
+    epoch.checked_sub(epoch.rem_euclid(step)).ok_or(EtError::Overflow)
+}
+
+/// Round `epoch` up to a multiple of `step` since the epoch. An epoch that
+/// is already a multiple stays where it is.
+pub fn ceil_to(epoch: i64, step: Duration) -> Result<i64> {
+    let step = multiple_of(step)?;
+    match epoch.rem_euclid(step) {
+        0 => Ok(epoch),
+        rem => epoch.checked_add(step - rem).ok_or(EtError::Overflow),
+    }
+}
+
+// Names
+//
+// Month and weekday names are always English, whatever the system locale, so
+// output stays machine-parseable. Everything `et` prints a name for goes
+// through these tables; RFC 2822 output comes from `time`, which uses fixed
+// English abbreviations as well.
+
+const MONTH_NAMES: [&str; 12] = [
+    "January", "February", "March", "April", "May", "June", "July", "August", "September",
+    "October", "November", "December",
+];
+
+const WEEKDAY_NAMES: [&str; 7] =
+    ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"];
+
+/// The English name of a month.
+pub fn month_name(month: Month) -> &'static str {
+    MONTH_NAMES[month as usize - 1]
+}
+
+/// The English name of a weekday.
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    WEEKDAY_NAMES[weekday.number_days_from_monday() as usize]
+}
+
+/// Parse a weekday from its English name or three-letter abbreviation, in
+/// any case (`mon`, `Monday`).
+pub fn parse_weekday(s: &str) -> Result<Weekday> {
+    let s = s.trim();
+    let index = WEEKDAY_NAMES
+        .iter()
+        .position(|name| s.eq_ignore_ascii_case(name) || s.eq_ignore_ascii_case(&name[..3]))
+        .ok_or_else(|| EtError::InvalidWeekday(s.to_string()))?;
+    Ok(Weekday::Monday.nth_next(index as u8))
+}
+
+/// Calendar fields of an epoch timestamp, in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Components {
+    pub year: i32,
+    /// Month of the year (1-12)
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+    pub weekday: Weekday,
+    /// Day of the year (1-366)
+    pub day_of_year: u16,
+}
+
+impl Components {
+    /// Render the fields as a single-line JSON object.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"year\":{},\"month\":{},\"day\":{},\"hour\":{},\"minute\":{},\"second\":{},\"weekday\":\"{}\",\"day_of_year\":{}}}",
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+            weekday_name(self.weekday),
+            self.day_of_year,
+        )
+    }
+}
+
+/// Break an epoch timestamp into its UTC calendar fields.
+pub fn decompose(epoch: i64) -> Result<Components> {
+    let dt = utc_datetime(epoch)?;
+
+    Ok(Components {
+        year: dt.year(),
+        month: dt.month() as u8,
+        day: dt.day(),
+        hour: dt.hour(),
+        minute: dt.minute(),
+        second: dt.second(),
+        weekday: dt.weekday(),
+        day_of_year: dt.ordinal(),
+    })
+}
+
+/// Build an epoch timestamp from UTC calendar fields.
+///
+/// Each field is validated, so month 13 or Feb 30 is an error rather than
+/// rolling over into the next month.
+pub fn compose(year: i32, month: u8, day: u8, hour: u8, minute: u8, second: u8) -> Result<i64> {
+    let month = Month::try_from(month)
+        .map_err(|_| EtError::InvalidDate(format!("month {month} is out of range")))?;
+    let date = Date::from_calendar_date(year, month, day)
+        .map_err(|_| EtError::InvalidDate(format!("{} {day}, {year} does not exist", month_name(month))))?;
+    let time = Time::from_hms(hour, minute, second).map_err(|_| {
+        EtError::InvalidDate(format!("{hour:02}:{minute:02}:{second:02} is not a valid time"))
+    })?;
+
+    Ok(date.with_time(time).assume_offset(UtcOffset::UTC).unix_timestamp())
+}
+
+/// Whole days from epoch 0 to `epoch`, rounding towards the past so that
+/// any time on 1969-12-31 is day -1.
+pub fn days_since_epoch(epoch: i64) -> i64 {
+    epoch.div_euclid(86400)
+}
+
+/// Whether `year` is a Gregorian leap year.
+pub fn is_leap_year(year: i32) -> bool {
+    time::util::is_leap_year(year)
+}
+
+/// Number of days in a month (1-12) of `year`.
+pub fn days_in_month(year: i32, month: u8) -> Result<u8> {
+    let month = Month::try_from(month)
+        .map_err(|_| EtError::InvalidDate(format!("month {month} is out of range")))?;
+    Ok(month.length(year))
+}
+
+/// The ISO 8601 week-numbering year and week (1-53) containing `epoch`, in
+/// UTC. Days at either end of a calendar year can belong to a week of the
+/// neighbouring year: 2024-12-30 is in week 1 of 2025.
+pub fn iso_week(epoch: i64) -> Result<(i32, u8)> {
+    let (year, week, _) = utc_datetime(epoch)?.date().to_iso_week_date();
+    Ok((year, week))
+}
+
+/// Which occurrence of its weekday in the UTC month `epoch` falls on, from 1
+/// to 5: the 8th through the 14th are always the second Monday, Tuesday,
+/// and so on.
+pub fn week_of_month(epoch: i64) -> Result<u8> {
+    Ok(weekday_occurrence(decompose(epoch)?.day))
+}
+
+fn weekday_occurrence(day: u8) -> u8 {
+    (day - 1) / 7 + 1
+}
+
+/// Midnight UTC on the `n`th `weekday` of a month (1-12), such as the 3rd
+/// Monday of March 2024. Negative `n` counts from the end of the month, so
+/// `-1` is the last one. An occurrence the month does not have (a 5th
+/// Friday in a month with four) is an error.
+pub fn nth_weekday(year: i32, month: u8, weekday: Weekday, n: i8) -> Result<i64> {
+    let length = days_in_month(year, month)?;
+    let month = Month::try_from(month).map_err(|_| EtError::Overflow)?;
+    let first = Date::from_calendar_date(year, month, 1)
+        .map_err(|_| EtError::InvalidDate(format!("{year} is out of range")))?;
+    let last = first.replace_day(length).map_err(|_| EtError::Overflow)?;
+
+    let days_between = |from: Weekday, to: Weekday| {
+        (to.number_days_from_monday() as i32 - from.number_days_from_monday() as i32).rem_euclid(7)
+    };
+    let day = match n {
+        1.. => 1 + days_between(first.weekday(), weekday) + 7 * (n as i32 - 1),
+        ..=-1 => length as i32 - days_between(weekday, last.weekday()) + 7 * (n as i32 + 1),
+        0 => return Err(EtError::InvalidDate("occurrence 0 does not exist; count from 1 or -1".to_string())),
+    };
+
+    let missing = || {
+        let which = match n {
+            -1 => "last".to_string(),
+            1.. => ordinal(n.unsigned_abs()),
+            _ => format!("{} from last", ordinal(n.unsigned_abs())),
+        };
+        EtError::InvalidDate(format!("{} {year} has no {which} {}", month_name(month), weekday_name(weekday)))
+    };
+    let day = u8::try_from(day).ok().filter(|d| (1..=length).contains(d)).ok_or_else(missing)?;
+    let date = first.replace_day(day).map_err(|_| EtError::Overflow)?;
+    Ok(date.midnight().assume_offset(UtcOffset::UTC).unix_timestamp())
+}
+
+/// `1st`, `2nd`, `3rd`, `4th`, ...
+fn ordinal(n: u8) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// Parse a time of day written `HH:MM` or `HH:MM:SS` (24-hour clock).
+pub fn parse_time_of_day(s: &str) -> Result<Time> {
+    let s = s.trim();
+    let invalid = || EtError::InvalidDate(format!("{s} is not a time of day such as 09:00 or 17:30:00"));
+    let parts: Vec<&str> = s.split(':').collect();
+    if !(2..=3).contains(&parts.len()) || parts.iter().any(|p| p.len() != 2) {
+        return Err(invalid());
+    }
+
+    let mut fields = [0u8; 3];
+    for (field, part) in fields.iter_mut().zip(&parts) {
+        *field = part.parse().map_err(|_| invalid())?;
+    }
+    Time::from_hms(fields[0], fields[1], fields[2]).map_err(|_| invalid())
+}
+
+/// The first moment strictly after `from` whose wall-clock time at `offset`
+/// is `time`: later the same day if that is still ahead, otherwise the
+/// next day.
+pub fn next_time_of_day(from: i64, time: Time, offset: UtcOffset) -> Result<i64> {
+    let same_day = time_of_day_on_same_date(from, time, offset)?;
+    let next = if same_day.unix_timestamp() > from {
+        Some(same_day)
+    } else {
+        same_day.checked_add(time::Duration::DAY)
+    };
+    Ok(next.ok_or(EtError::Overflow)?.unix_timestamp())
+}
+
+/// The last moment strictly before `from` whose wall-clock time at `offset`
+/// is `time`: earlier the same day if that has passed, otherwise the day
+/// before.
+pub fn prev_time_of_day(from: i64, time: Time, offset: UtcOffset) -> Result<i64> {
+    let same_day = time_of_day_on_same_date(from, time, offset)?;
+    let prev = if same_day.unix_timestamp() < from {
+        Some(same_day)
+    } else {
+        same_day.checked_sub(time::Duration::DAY)
+    };
+    Ok(prev.ok_or(EtError::Overflow)?.unix_timestamp())
+}
+
+/// `time` on the date that `from` falls on at `offset`.
+fn time_of_day_on_same_date(from: i64, time: Time, offset: UtcOffset) -> Result<OffsetDateTime> {
+    let date = utc_datetime(from)?
+        .to_offset(offset)
+        .date();
+    Ok(date.with_time(time).assume_offset(offset))
+}
+
+/// Format an epoch in decimal, left-padded with zeros to `width` characters.
+///
+/// The sign of a negative epoch stays in front of the zeros and counts
+/// towards the width (`pad_epoch(-42, 5)` is `-0042`).
+pub fn pad_epoch(epoch: i64, width: usize) -> String {
+    format!("{epoch:0width$}")
+}
+
+/// Whether an epoch fits in a signed 32-bit `time_t`, which runs out after
+/// 2038-01-19T03:14:07Z (the Year 2038 problem).
+pub fn fits_in_i32(epoch: i64) -> bool {
+    i32::try_from(epoch).is_ok()
+}
+
+/// Whether an epoch fits in an unsigned 32-bit counter, which covers
+/// 1970 through 2106-02-07T06:28:15Z but nothing before 1970.
+pub fn fits_in_u32(epoch: i64) -> bool {
+    u32::try_from(epoch).is_ok()
+}
+
+/// Whether `s` is an epoch with a unit suffix that `Timestamp::parse_epoch`
+/// reads, rather than a duration. `ms`, `us`, and `ns` are not duration
+/// units, so any count of them is an epoch; with `s`, an unsigned count of
+/// ten or more digits (`1704912345s`) is an epoch too.
+fn is_suffixed_epoch(s: &str) -> bool {
+    let Some((number, unit)) = ["ms", "us", "ns", "s"]
+        .into_iter()
+        .find_map(|unit| Some((s.strip_suffix(unit)?, unit)))
+    else {
+        return false;
+    };
+    let digits = number.strip_prefix(['+', '-']).unwrap_or(number);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    unit != "s" || (digits.len() == number.len() && digits.len() >= 10)
+}
+
+/// Check if a string looks like a duration.
+///
+/// A duration is an optional sign, one or more digits with an optional
+/// decimal fraction, and a unit made of letters (`3h`, `-7d`, `1.5h`,
+/// `2weeks`), or clock notation such as `01:30:00`. A signed bare number (`-3600`) counts as
+/// seconds; an unsigned bare number is an epoch, not a duration, and so is
+/// an epoch with a unit suffix (see `is_suffixed_epoch`). Only the shape is
+/// checked here; unknown units are reported by `Duration::parse`.
+pub fn is_duration(s: &str) -> bool {
+    let s = s.trim();
+    if is_suffixed_epoch(s) {
+        return false;
+    }
+    let (signed, rest) = match s.strip_prefix(['+', '-']) {
+        Some(stripped) => (true, stripped),
+        None => (false, s),
+    };
+
+    // Clock notation: two or three colon-separated groups of digits
+    if rest.contains(':') {
+        let parts: Vec<&str> = rest.split(':').collect();
+        return (2..=3).contains(&parts.len())
+            && parts
+                .iter()
+                .all(|p| !p.is_empty() && p.bytes().all(|b| b.is_ascii_digit()));
+    }
+
+    let digit_end = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    if digit_end == 0 {
+        return false;
+    }
+
+    // Allow one decimal point followed by more digits
+    let unit = match rest[digit_end..].strip_prefix('.') {
+        Some(after) => {
+            let fraction_end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            if fraction_end == 0 {
+                return false;
+            }
+            &after[fraction_end..]
+        }
+        None => &rest[digit_end..],
+    };
+    if unit.is_empty() {
+        return signed;
+    }
+
+    unit.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// Configuration
+
+/// Defaults read from the config file. Every setting is optional, and flags
+/// and environment variables take precedence over all of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Config {
+    /// Output style for `format`, like `--format`
+    pub format: Option<String>,
+    pub precision: Option<Precision>,
+    /// Offset for ISO-8601 timestamps without a timezone, like `--assume-timezone`
+    pub timezone: Option<UtcOffset>,
+    pub week_start: Option<WeekStart>,
+}
+
+impl Config {
+    /// Parse a config file written in TOML. Settings are top-level keys
+    /// with string values; an unknown key or a value that does not parse
+    /// is an error naming its line.
+    pub fn parse(s: &str) -> Result<Self> {
+        let raw: RawConfig =
+            toml::from_str(s).map_err(|e| config_error(s, e.span(), e.message()))?;
+        Ok(Config {
+            format: raw.format.map(Spanned::into_inner),
+            precision: config_setting(s, raw.precision, str::parse)?,
+            timezone: config_setting(s, raw.timezone, parse_offset)?,
+            week_start: config_setting(s, raw.week_start, str::parse)?,
+        })
+    }
+}
+
+/// The settings as written in the file, before their values are parsed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    format: Option<Spanned<String>>,
+    precision: Option<Spanned<String>>,
+    timezone: Option<Spanned<String>>,
+    week_start: Option<Spanned<String>>,
+}
+
+/// Parse a setting's value from the config file `s`, reporting a failure
+/// at the value's line.
+fn config_setting<T>(
+    s: &str,
+    value: Option<Spanned<String>>,
+    parse: impl FnOnce(&str) -> Result<T>,
+) -> Result<Option<T>> {
+    let Some(value) = value else {
+        return Ok(None);
+    };
+    parse(value.get_ref())
+        .map(Some)
+        .map_err(|e| config_error(s, Some(value.span()), &e.to_string()))
+}
+
+/// An `InvalidConfig` error for the line of `s` that `span` starts on.
+fn config_error(s: &str, span: Option<Range<usize>>, message: &str) -> EtError {
+    let line = span.map_or(1, |span| s[..span.start].matches('\n').count() + 1);
+    EtError::InvalidConfig(format!("line {line}: {}", message.trim_end()))
 }
@@ -1,8 +1,28 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
 use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use clap::{Parser, Subcommand};
-use et::{apply_duration, format_iso, is_duration, now, parse_epoch, parse_iso, Duration, EtError};
+use clap::error::ErrorKind;
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use et::{
+    ceil_to, check_strict_iso, color_iso, color_relative, compose, count_steps, days_in_month,
+    days_since_epoch, decompose, explain_duration, fits_in_i32, fits_in_u32, floor_to,
+    format_iso_at, format_offset, format_with, humanize_duration, humanize_relative_parts,
+    is_duration, is_leap_year, is_range, iso_week, local_offset, next_start_of, next_time_of_day,
+    normalize_iso, now, nth_weekday, parse_any_timestamp, parse_epoch, parse_iso,
+    parse_iso_assuming, parse_offset, parse_time_of_day, parse_weekday, parse_with,
+    prev_time_of_day, sequence, sequence_len, since, start_of, until, weekday_name,
+    Config, Duration, EpochRange, EtError, InputFormat, Period, Precision, RandomEpochs, Timestamp,
+    WeekStart, YearMode,
+};
+use time::{Time, UtcOffset};
 
 #[derive(Parser, Debug)]
 #[command(
@@ -17,10 +37,15 @@ use et::{apply_duration, format_iso, is_duration, now, parse_epoch, parse_iso, D
                     d    days (86400s)\n  \
                     w    weeks (604800s)\n  \
                     M    months (calendar)\n  \
-                    Y    years (calendar)\n\n\
+                    Y    years (calendar)\n  \
+                    c    centuries (calendar)\n\n\
+                  Word forms such as 'hours', 'fortnight' (14 days), and\n\
+                  'decade' (10 years) are also accepted.\n\n\
                   Calendar units handle variable-length months and leap years.\n\
                   When adding months, days are clamped to valid range\n\
-                  (e.g., Jan 31 + 1M = Feb 28/29).",
+                  (e.g., Jan 31 + 1M = Feb 28/29).\n\n\
+                  Negative durations such as -7d are read as values, not flags.\n\
+                  Everything after a bare -- is always a value: et now -- -7d",
     after_help = "EXAMPLES\n  \
                   et                  Print current epoch\n  \
                   et -7d              Subtract 7 days\n  \
@@ -28,125 +53,1772 @@ use et::{apply_duration, format_iso, is_duration, now, parse_epoch, parse_iso, D
                   et +1M              Add 1 month\n  \
                   et -1Y              Subtract 1 year\n  \
                   et 1704912345 +1h   Add 1 hour to given epoch\n  \
+                  et sub now 1d       Subtract 1 day from now\n  \
+                  et now -- -7d       Subtract 7 days, with -7d marked as a value\n  \
+                  et 0..=259200 1d    Print every day from 0 to 259200\n  \
                   et parse 2026-01-05T12:00:00Z\n  \
                   et format 1704912345\n  \
+                  et components 1704912345\n  \
+                  et make --year 2024 --month 1 --day 10 --hour 12\n  \
+                  et until 2030-01-01T00:00:00Z --human\n  \
+                  et random --from 2020-01-01T00:00:00Z --to 2025-01-01T00:00:00Z --count 10\n  \
                   echo 1704912345 | et -1d"
 )]
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
 
-    /// Epoch, duration, or 'now'
+    /// Epoch, duration, or 'now'; after a bare --, always read as a value
     #[arg(value_name = "ARG", allow_hyphen_values = true)]
-    args: Vec<String>,
+    arg: Option<String>,
+
+    /// Duration offset to apply to ARG (e.g., +3h, -7d) [default: $ET_DURATION]
+    #[arg(value_name = "DURATION", allow_hyphen_values = true)]
+    duration: Option<String>,
+
+    /// Print each epoch result, from arguments or stdin, as ISO-8601
+    /// instead of a number
+    #[arg(long, global = true, conflicts_with_all = ["template", "explain", "annotate"])]
+    iso: bool,
+
+    /// Count stdin timestamps in buckets of a fixed DURATION (e.g. 1h) and
+    /// print "BUCKET COUNT" lines, each bucket named by its first epoch
+    #[arg(long, value_name = "DURATION", conflicts_with_all = ["arg", "duration", "template", "explain", "iso"])]
+    group_by: Option<String>,
+
+    /// Check that every duration argument parses and print each in normalized
+    /// form, without computing a result
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Fail when piped stdin is empty instead of falling back to the current time
+    #[arg(long, global = true)]
+    no_fallback: bool,
+
+    /// Stop with an error if stdin has more than N timestamps
+    #[arg(long, value_name = "N", global = true)]
+    max_lines: Option<usize>,
+
+    /// Read stdin lines as ISO-8601 timestamps instead of epochs, printing
+    /// each as an epoch
+    #[arg(long, global = true, conflicts_with = "auto_precision")]
+    from_iso: bool,
+
+    /// Render ISO-8601 output at this UTC offset instead of UTC (e.g.
+    /// -08:00); outside `et format`, only with --iso
+    #[arg(long, value_name = "OFFSET", global = true, allow_hyphen_values = true)]
+    output_tz: Option<String>,
+
+    /// Report bad stdin lines on stderr and keep going instead of stopping
+    #[arg(long, global = true)]
+    skip_errors: bool,
+
+    /// Guess each stdin epoch's unit from its digit count (10 digits for
+    /// seconds, 13 for ms, 16 for us, 19 for ns) and print it at --precision
+    #[arg(long, global = true)]
+    auto_precision: bool,
+
+    /// Print each result, from arguments or stdin, through TEMPLATE, e.g. "{input} -> {result}";
+    /// placeholders are {input}, {result}, and {iso}, and {{ and }} are literal braces
+    #[arg(long, value_name = "TEMPLATE", global = true, conflicts_with_all = ["explain", "annotate"])]
+    template: Option<Template>,
+
+    /// Left-pad numeric epoch output with zeros to N characters
+    #[arg(long, value_name = "N", global = true)]
+    pad: Option<usize>,
+
+    /// Count epochs in seconds (s), milliseconds (ms), microseconds (us), or nanoseconds (ns)
+    /// [default: the config file, or s]
+    #[arg(long, value_name = "UNIT", global = true, env = "ET_DEFAULT_PRECISION")]
+    precision: Option<String>,
+
+    /// Join multiple results with STR instead of newlines
+    #[arg(long, value_name = "STR", global = true)]
+    separator: Option<String>,
+
+    /// Join multiple results with spaces on a single line
+    #[arg(long, global = true, conflicts_with = "separator")]
+    oneline: bool,
+
+    /// Color ISO-8601 and relative-time output
+    #[arg(long, value_name = "WHEN", global = true, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Describe what applying a duration does instead of printing the result
+    #[arg(long, global = true)]
+    explain: bool,
+
+    /// Flip the sign of the duration before applying it
+    #[arg(long, global = true)]
+    negate: bool,
+
+    /// Apply years on the calendar (Feb 29 + 1Y is Feb 28) or as the exact
+    /// days they span (Feb 29 + 1Y is Mar 1): calendar or exact-days [default: calendar]
+    #[arg(long, value_name = "MODE", global = true)]
+    year_mode: Option<YearMode>,
+
+    /// Repeat the duration N times before applying it (e.g., --times 5 with 1w)
+    #[arg(long, value_name = "N", global = true, allow_negative_numbers = true)]
+    times: Option<i32>,
+
+    /// Clamp results that overflow to the smallest or largest 64-bit epoch
+    /// instead of failing
+    #[arg(long, global = true, conflicts_with = "explain")]
+    saturate: bool,
+
+    /// Follow each epoch with its ISO-8601 form in parentheses
+    #[arg(long, global = true)]
+    annotate: bool,
+
+    /// Print a warning on stderr when a result is exactly epoch 0, a common
+    /// placeholder for missing data
+    #[arg(long, global = true)]
+    warn_epoch_zero: bool,
+
+    /// Do not print the trailing newline after the last result
+    #[arg(short = 'n', long, global = true)]
+    no_newline: bool,
+
+    /// Read ISO-8601 timestamps without a timezone at this UTC offset (e.g. +05:30);
+    /// timestamps that carry their own timezone ignore it
+    #[arg(
+        long,
+        visible_alias = "input-tz",
+        value_name = "OFFSET",
+        global = true,
+        allow_hyphen_values = true,
+        env = "ET_ASSUME_TIMEZONE"
+    )]
+    assume_timezone: Option<String>,
+
+    /// Accept lowercase 't' and 'z' in ISO-8601 timestamps
+    #[arg(long, global = true)]
+    lenient: bool,
+
+    /// Only accept ISO-8601 in the strict RFC 3339 shape: YYYY-MM-DDTHH:MM:SS
+    /// with Z or a colon-separated offset such as +02:00
+    #[arg(long, global = true, conflicts_with = "lenient")]
+    strict_iso: bool,
+
+    /// Use EPOCH as the current time instead of the system clock
+    #[arg(
+        long = "now",
+        value_name = "EPOCH",
+        global = true,
+        allow_hyphen_values = true,
+        env = "SOURCE_DATE_EPOCH"
+    )]
+    now_override: Option<String>,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ColorChoice {
+    /// Color when stdout is a terminal and NO_COLOR is unset
+    Auto,
+    Always,
+    Never,
+}
+
+/// A kind of timestamp input, for `validate --require`.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum InputKind {
+    Epoch,
+    Iso,
+}
+
+/// Writes results to stdout, applying the output settings shared by every
+/// command. Each result is a record; records are joined by the separator and
+/// the output ends with a single newline unless `--no-newline` is given.
+struct Output {
+    stdout: io::StdoutLock<'static>,
+    pad: Option<usize>,
+    separator: String,
+    newline: bool,
+    color: bool,
+    explain: bool,
+    negate: bool,
+    times: Option<i32>,
+    year_mode: YearMode,
+    saturate: bool,
+    annotate: bool,
+    /// Print epoch results as ISO-8601 at this offset (`--iso`)
+    iso: Option<UtcOffset>,
+    template: Option<Template>,
+    warn_epoch_zero: bool,
+    records: usize,
+}
+
+impl Output {
+    fn new(cli: &Cli) -> et::Result<Self> {
+        let separator = match (&cli.separator, cli.oneline) {
+            (Some(sep), _) => sep.clone(),
+            (None, true) => " ".to_string(),
+            (None, false) => "\n".to_string(),
+        };
+
+        let color = match cli.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+        };
+
+        let iso = match (cli.iso, &cli.output_tz) {
+            (false, _) => None,
+            (true, Some(offset)) => Some(parse_offset(offset)?),
+            (true, None) => Some(UtcOffset::UTC),
+        };
+
+        Ok(Output {
+            stdout: io::stdout().lock(),
+            pad: cli.pad,
+            separator,
+            newline: !cli.no_newline,
+            color,
+            explain: cli.explain,
+            negate: cli.negate,
+            times: cli.times,
+            year_mode: cli.year_mode.unwrap_or_default(),
+            saturate: cli.saturate,
+            annotate: cli.annotate,
+            iso,
+            template: cli.template.clone(),
+            warn_epoch_zero: cli.warn_epoch_zero,
+            records: 0,
+        })
+    }
+
+    /// Start a new record, writing the separator if one came before.
+    fn separate(&mut self) -> io::Result<()> {
+        if self.records > 0 {
+            self.stdout.write_all(self.separator.as_bytes())?;
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    /// Write a single result.
+    fn record(&mut self, value: &str) -> io::Result<()> {
+        self.separate()?;
+        self.stdout.write_all(value.as_bytes())
+    }
+
+    /// Warn on stderr about an epoch 0 result with `--warn-epoch-zero`.
+    fn check_zero(&self, ts: Timestamp) {
+        if self.warn_epoch_zero && ts.value == 0 {
+            eprintln!("warning: result is epoch 0 (1970-01-01T00:00:00Z), which often stands for missing data");
+        }
+    }
+
+    /// Write a numeric epoch result counted at its own precision: ISO-8601
+    /// with `--iso`, otherwise the number, padded and annotated as asked.
+    fn timestamp(&mut self, ts: Timestamp) -> et::Result<()> {
+        if let Some(offset) = self.iso {
+            let text = self.iso_at_text(ts, offset)?;
+            self.record(&text)?;
+            return Ok(());
+        }
+        self.check_zero(ts);
+        self.separate()?;
+        write_padded(&mut self.stdout, ts, self.pad)?;
+        if self.annotate {
+            self.stdout.write_all(b" (")?;
+            self.write_iso(ts)?;
+            self.stdout.write_all(b")")?;
+        }
+        Ok(())
+    }
+
+    /// Write an epoch followed by `text` in parentheses, for `--annotate`.
+    fn annotated(&mut self, ts: Timestamp, text: &str) -> et::Result<()> {
+        self.separate()?;
+        write_padded(&mut self.stdout, ts, self.pad)?;
+        write!(self.stdout, " ({text})")?;
+        Ok(())
+    }
+
+    /// `duration` as the user asked for it from `epoch`: scaled by
+    /// `--times`, flipped by `--negate`, then counted in exact days under
+    /// `--year-mode exact-days`. The days depend on the direction, so they
+    /// are counted last.
+    fn adjusted(&self, epoch: Timestamp, duration: Duration) -> et::Result<Duration> {
+        let duration = match self.times {
+            Some(factor) => duration.scale(factor)?,
+            None => duration,
+        };
+        let duration = if self.negate { duration.negate()? } else { duration };
+        match self.year_mode {
+            YearMode::Calendar => Ok(duration),
+            YearMode::ExactDays => duration.in_exact_days(epoch.as_seconds()?),
+        }
+    }
+
+    /// `epoch` moved by `duration`, clamped instead of failing with
+    /// `--saturate`.
+    fn apply(&self, epoch: Timestamp, duration: Duration) -> et::Result<Timestamp> {
+        if self.saturate { epoch.apply_saturating(duration) } else { epoch.apply(duration) }
+    }
+
+    /// Write the result for `input`, read as `epoch`, applying `duration` if
+    /// given. `--times` scales and `--negate` flips the duration first, and
+    /// `--explain` describes the operation instead. With a `--template`, the
+    /// result is rendered through it.
+    fn line(&mut self, input: &str, epoch: Timestamp, duration: Option<Duration>) -> et::Result<()> {
+        let result = match duration {
+            Some(d) => {
+                let d = self.adjusted(epoch, d)?;
+                if self.explain && self.template.is_none() {
+                    let text = explain_duration(epoch.as_seconds()?, d)?.describe()?;
+                    self.record(&text)?;
+                    return Ok(());
+                }
+                self.apply(epoch, d)?
+            }
+            None => epoch,
+        };
+        if self.template.is_none() {
+            return self.timestamp(result);
+        }
+        self.separate()?;
+        if let Some(template) = &self.template {
+            template.write(&mut self.stdout, input, result, self.pad)?;
+        }
+        Ok(())
+    }
+
+    /// Write an epoch result as ISO-8601.
+    fn iso(&mut self, ts: Timestamp) -> et::Result<()> {
+        self.check_zero(ts);
+        self.separate()?;
+        self.write_iso(ts)
+    }
+
+    /// Write `ts` as ISO-8601 UTC into the current record, colored if color
+    /// is enabled.
+    fn write_iso(&mut self, ts: Timestamp) -> et::Result<()> {
+        if self.color {
+            self.stdout.write_all(color_iso(&ts.format_iso()?).as_bytes())?;
+            return Ok(());
+        }
+        ts.write_iso(&mut self.stdout)
+    }
+
+    /// An epoch result as ISO-8601 wall-clock time at `offset`.
+    fn iso_at_text(&self, ts: Timestamp, offset: UtcOffset) -> et::Result<String> {
+        self.check_zero(ts);
+        let text = ts.format_with_at("iso", offset)?;
+        Ok(if self.color { color_iso(&text) } else { text })
+    }
+
+    /// Color a `humanize_relative` description if color is enabled.
+    fn paint_relative(&self, text: String) -> String {
+        if self.color { color_relative(&text) } else { text }
+    }
+
+    /// Terminate the output once all records are written.
+    fn finish(&mut self) -> io::Result<()> {
+        if self.records > 0 && self.newline {
+            self.stdout.write_all(b"\n")?;
+        }
+        self.stdout.flush()
+    }
 }
 
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Print current epoch timestamp
+    #[command(after_help = "EXAMPLES\n  \
+                           et now\n  \
+                           et --now 1704912345 now +1h\n    \
+                           1704915945")]
     Now {
         /// Duration offset (e.g., +3h, -7d)
         #[arg(value_name = "DURATION", allow_hyphen_values = true)]
         duration: Option<String>,
     },
 
-    /// Convert ISO-8601 timestamp to epoch
+    /// Add a duration to an epoch timestamp (default: now)
+    #[command(
+        override_usage = "et add [EPOCH] <DURATION>",
+        after_help = "EXAMPLES\n  \
+                     et add 1704912345 1h\n    \
+                     1704915945\n  \
+                     et add 1704912345 1M\n    \
+                     1707590745"
+    )]
+    Add {
+        /// Optional epoch or 'now', followed by a duration (e.g., 3h)
+        #[arg(value_name = "ARGS", num_args = 1..=2, required = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Subtract a duration from an epoch timestamp (default: now)
+    #[command(
+        override_usage = "et sub [EPOCH] <DURATION>",
+        after_help = "EXAMPLES\n  \
+                     et sub 1704912345 1d\n    \
+                     1704825945"
+    )]
+    Sub {
+        /// Optional epoch or 'now', followed by a duration (e.g., 1d)
+        #[arg(value_name = "ARGS", num_args = 1..=2, required = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
+    /// Convert a timestamp to epoch, detecting its format
+    ///
+    /// Without --input-format, the formats are tried in this order and the
+    /// first that fits wins: epoch, ISO-8601, RFC 2822, RFC 3339 with a space
+    /// separator, then a bare YYYY-MM-DD date (midnight UTC).
+    #[command(after_help = "EXAMPLES\n  \
+                           et parse 2024-01-10T18:45:45Z\n    \
+                           1704912345\n  \
+                           et parse 'Wed, 10 Jan 2024 18:45:45 +0000'\n    \
+                           1704912345\n  \
+                           et parse 2024-01-10\n    \
+                           1704844800")]
     Parse {
-        /// ISO-8601 timestamp with timezone (e.g., 2026-01-05T12:00:00Z)
+        /// Timestamp with timezone (e.g., 2026-01-05T12:00:00Z or "Mon, 05 Jan 2026 12:00:00 +0000")
         #[arg(value_name = "TIMESTAMP")]
         timestamp: String,
+
+        /// Only accept this format: epoch, iso, rfc2822, rfc3339, or date
+        #[arg(long, value_name = "FORMAT")]
+        input_format: Option<InputFormat>,
+
+        /// Interpret a timestamp without a timezone as local time (not with
+        /// --assume-timezone, which sets the timezone too)
+        #[arg(long)]
+        local: bool,
+
+        /// Round fractional seconds to the nearest unit instead of truncating
+        #[arg(long)]
+        round: bool,
     },
 
     /// Convert epoch timestamp to ISO-8601
+    #[command(after_help = "EXAMPLES\n  \
+                           et format 1704912345\n    \
+                           2024-01-10T18:45:45Z\n  \
+                           et format 1704912345 --format rfc2822\n    \
+                           Wed, 10 Jan 2024 18:45:45 +0000\n  \
+                           et format 1704912345 --output-tz -05:00\n    \
+                           2024-01-10T13:45:45-05:00")]
     Format {
+        /// Epoch (in seconds unless --precision says otherwise) or ISO-8601 timestamp
+        #[arg(value_name = "TIMESTAMP", required_unless_present = "input")]
+        epoch: Option<String>,
+
+        /// Read the timestamp from this file instead of the command line
+        #[arg(long, value_name = "PATH", conflicts_with = "epoch")]
+        input: Option<PathBuf>,
+
+        /// iso, rfc3339, rfc2822, iso-basic, wom (weekday occurrence in the month), or a pattern like "[year]/[month]/[day]"
+        /// [default: $ET_FORMAT, the config file, or iso]
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<String>,
+
+        /// Also print the number of whole days since 1970-01-01
+        #[arg(long)]
+        days_since_epoch: bool,
+    },
+
+    /// Break an epoch timestamp into its calendar fields
+    #[command(after_help = "EXAMPLES\n  \
+                           et components 1704912345\n    \
+                           year: 2024\n    \
+                           month: 1\n    \
+                           day: 10\n    \
+                           hour: 18\n    \
+                           minute: 45\n    \
+                           second: 45\n    \
+                           weekday: Wednesday\n    \
+                           day_of_year: 10")]
+    Components {
         /// Epoch timestamp in seconds
-        #[arg(value_name = "EPOCH")]
+        #[arg(value_name = "EPOCH", allow_hyphen_values = true)]
         epoch: String,
+
+        /// Print the fields as a JSON object
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print the seconds (or --precision units) elapsed since a timestamp
+    #[command(after_help = "EXAMPLES\n  \
+                           et since 1704912345 --relative-to 1704916000\n    \
+                           3655\n  \
+                           et since 1704912345 --relative-to 1705000000 --human\n    \
+                           1 day ago")]
+    Since {
+        /// Epoch seconds or ISO-8601 timestamp
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: String,
+
+        /// Describe the elapsed time in words
+        #[arg(long)]
+        human: bool,
+
+        /// With --human, use compact units such as 3h or 1mo
+        #[arg(long, requires = "human")]
+        abbrev: bool,
+
+        /// With --human, show the N largest units, as in "1 day 3 hours" [default: 1]
+        #[arg(long, value_name = "N", requires = "human")]
+        parts: Option<usize>,
+
+        /// Measure against this epoch or ISO-8601 timestamp instead of now
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        relative_to: Option<String>,
+    },
+
+    /// Print the seconds (or --precision units) remaining until a timestamp
+    #[command(after_help = "EXAMPLES\n  \
+                           et until 1704916000 --relative-to 1704912345\n    \
+                           3655\n  \
+                           et until 2024-02-01T00:00:00Z --relative-to 1704912345 --human\n    \
+                           in 3 weeks")]
+    Until {
+        /// Epoch seconds or ISO-8601 timestamp
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: String,
+
+        /// Describe the remaining time in words
+        #[arg(long)]
+        human: bool,
+
+        /// With --human, use compact units such as 3h or 1mo
+        #[arg(long, requires = "human")]
+        abbrev: bool,
+
+        /// With --human, show the N largest units, as in "1 day 3 hours" [default: 1]
+        #[arg(long, value_name = "N", requires = "human")]
+        parts: Option<usize>,
+
+        /// Measure against this epoch or ISO-8601 timestamp instead of now
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        relative_to: Option<String>,
+    },
+
+    /// Break a number of seconds into days, hours, minutes, and seconds
+    #[command(after_help = "EXAMPLES\n  \
+                           et fmt-duration 90061\n    \
+                           1d 1h 1m 1s")]
+    FmtDuration {
+        /// Number of seconds (e.g., 90061)
+        #[arg(value_name = "SECONDS", allow_hyphen_values = true)]
+        seconds: String,
+    },
+
+    /// Count how many whole steps separate two timestamps
+    #[command(after_help = "EXAMPLES\n  \
+                           et between 2024-01-01T00:00:00Z 2024-03-15T00:00:00Z --step 1M\n    \
+                           2")]
+    Between {
+        /// Epoch or ISO-8601 timestamp to count from
+        #[arg(value_name = "FROM", allow_hyphen_values = true)]
+        from: String,
+
+        /// Epoch or ISO-8601 timestamp to count to
+        #[arg(value_name = "TO", allow_hyphen_values = true)]
+        to: String,
+
+        /// Step to count (e.g., 1d, 1M); a partial final step is not counted
+        #[arg(long, value_name = "DURATION", allow_hyphen_values = true)]
+        step: String,
+    },
+
+    /// Print timestamps across a range, one step apart
+    #[command(after_help = "EXAMPLES\n  \
+                           et seq 0..=172800 --step 1d\n    \
+                           0\n    \
+                           86400\n    \
+                           172800\n  \
+                           et seq 0..86400 --step 1h --count-only\n    \
+                           24")]
+    Seq {
+        /// Range as START..END (END excluded) or START..=END (END included)
+        #[arg(value_name = "RANGE", allow_hyphen_values = true)]
+        range: String,
+
+        /// Step between timestamps (e.g., 1d, 15m, 1M); negative for descending ranges
+        #[arg(long, value_name = "DURATION", allow_hyphen_values = true)]
+        step: String,
+
+        /// Print how many timestamps the range holds instead of the timestamps
+        #[arg(long)]
+        count_only: bool,
+    },
+
+    /// Print the first second of the period containing a timestamp
+    #[command(after_help = "EXAMPLES\n  \
+                           et start-of day 1704912345\n    \
+                           1704844800\n  \
+                           et start-of month 1704912345\n    \
+                           1704067200")]
+    StartOf {
+        /// minute, hour, day, week, month, quarter, or year
+        #[arg(value_name = "PERIOD")]
+        period: Period,
+
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// First day of the week: mon (ISO) or sun (US) [default: mon]
+        #[arg(long, value_name = "DAY")]
+        week_start: Option<WeekStart>,
+    },
+
+    /// Print the last second of the period containing a timestamp
+    #[command(after_help = "EXAMPLES\n  \
+                           et end-of day 1704912345\n    \
+                           1704931199\n  \
+                           et end-of week 1704912345 --exclusive-end\n    \
+                           1705276800")]
+    EndOf {
+        /// minute, hour, day, week, month, quarter, or year
+        #[arg(value_name = "PERIOD")]
+        period: Period,
+
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// First day of the week: mon (ISO) or sun (US) [default: mon]
+        #[arg(long, value_name = "DAY")]
+        week_start: Option<WeekStart>,
+
+        /// Print the first second of the next period (00:00:00) instead of 23:59:59
+        #[arg(long)]
+        exclusive_end: bool,
+    },
+
+    /// Print the first and last second of the period containing a timestamp,
+    /// tab-separated
+    #[command(after_help = "EXAMPLES\n  \
+                           et range quarter 1704912345 --json\n    \
+                           {\"start\":1704067200,\"end\":1711929599}")]
+    Range {
+        /// minute, hour, day, week, month, quarter, or year
+        #[arg(value_name = "PERIOD")]
+        period: Period,
+
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// First day of the week: mon (ISO) or sun (US) [default: mon]
+        #[arg(long, value_name = "DAY")]
+        week_start: Option<WeekStart>,
+
+        /// Print {"start":...,"end":...} instead
+        #[arg(long)]
+        json: bool,
+
+        /// End at the first second of the next period (00:00:00) instead of 23:59:59
+        #[arg(long)]
+        exclusive_end: bool,
+    },
+
+    /// Print the first and last second of the ISO week containing a
+    /// timestamp and its label, such as 2024-W11, tab-separated
+    #[command(
+        visible_alias = "whatweek",
+        after_help = "EXAMPLES\n  \
+                      et week 2024-03-13T00:00:00Z\n    \
+                      1710115200\t1710719999\t2024-W11"
+    )]
+    Week {
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+    },
+
+    /// Print the next time the clock shows a time of day (e.g., 09:00)
+    #[command(after_help = "EXAMPLES\n  \
+                           et next-time 09:00 --from 1704912345\n    \
+                           1704963600\n  \
+                           et next-time 09:00 --from 1704912345 --offset +05:30\n    \
+                           1704943800")]
+    NextTime {
+        /// Time of day as HH:MM or HH:MM:SS
+        #[arg(value_name = "TIME")]
+        time: String,
+
+        /// Count from this epoch or ISO-8601 timestamp instead of now
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        from: Option<String>,
+
+        /// UTC offset the time of day is read at [default: UTC]
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+        offset: Option<String>,
+    },
+
+    /// Print the last time the clock showed a time of day (e.g., 09:00)
+    #[command(after_help = "EXAMPLES\n  \
+                           et prev-time 09:00 --from 1704912345\n    \
+                           1704877200")]
+    PrevTime {
+        /// Time of day as HH:MM or HH:MM:SS
+        #[arg(value_name = "TIME")]
+        time: String,
+
+        /// Count from this epoch or ISO-8601 timestamp instead of now
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        from: Option<String>,
+
+        /// UTC offset the time of day is read at [default: UTC]
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+        offset: Option<String>,
+    },
+
+    /// Round a timestamp down to a multiple of a fixed duration
+    #[command(after_help = "EXAMPLES\n  \
+                           et floor 1704912345 --to 15m\n    \
+                           1704912300")]
+    Floor {
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// Multiple to round to (e.g., 15m, 1h)
+        #[arg(long, value_name = "DURATION")]
+        to: String,
+    },
+
+    /// Round a timestamp up to a multiple of a fixed duration
+    #[command(after_help = "EXAMPLES\n  \
+                           et ceil 1704912345 --to 1h\n    \
+                           1704913200")]
+    Ceil {
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// Multiple to round to (e.g., 15m, 1h)
+        #[arg(long, value_name = "DURATION")]
+        to: String,
+    },
+
+    /// Convert a time between two UTC offsets and print the difference
+    #[command(after_help = "EXAMPLES\n  \
+                           et tz-offset --from +02:00 --to -05:00 2024-01-10T12:00:00\n    \
+                           2024-01-10T05:00:00-05:00\n    \
+                           -7h")]
+    TzOffset {
+        /// Offset the timestamp is given in (e.g., +02:00)
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+        from: String,
+
+        /// Offset to convert to (e.g., -05:00)
+        #[arg(long, value_name = "OFFSET", allow_hyphen_values = true)]
+        to: String,
+
+        /// Epoch or ISO-8601 timestamp; without a timezone it is read at --from (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+
+        /// Print the difference as signed seconds (e.g. -25200) instead of -7h
+        #[arg(long)]
+        seconds: bool,
+    },
+
+    /// Print the system's current UTC offset, e.g. -05:00
+    #[command(after_help = "EXAMPLES\n  \
+                           et offset-now")]
+    OffsetNow {
+        /// Print the offset as signed seconds (e.g. -18000) instead of ±HH:MM
+        #[arg(long)]
+        seconds: bool,
+    },
+
+    /// Print random timestamps between two times, for test data
+    #[command(after_help = "EXAMPLES\n  \
+                           et random --from 2024-01-01T00:00:00Z --to 2025-01-01T00:00:00Z --count 3 --seed 42\n    \
+                           1727517261\n    \
+                           1709123950\n    \
+                           1712877236")]
+    Random {
+        /// Earliest timestamp (epoch or ISO-8601), included
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        from: String,
+
+        /// Latest timestamp (epoch or ISO-8601), excluded
+        #[arg(long, value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        to: String,
+
+        /// Number of timestamps to print
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+
+        /// Seed for reproducible output (default: from the system clock)
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Read `EPOCH DURATION` pairs from stdin and apply each duration to its epoch
+    #[command(after_help = "EXAMPLES\n  \
+                           echo '1704912345 +1h' | et apply\n    \
+                           1704915945")]
+    Apply,
+
+    /// Read timestamps from stdin in one format and print them in another
+    #[command(after_help = "EXAMPLES\n  \
+                           echo '01/10/2024 12:00' | et transcode --from-format '[month]/[day]/[year] [hour]:[minute]'\n    \
+                           2024-01-10T12:00:00Z")]
+    Transcode {
+        /// Input format: epoch, iso, rfc2822, rfc3339, date, or a `time`
+        /// pattern such as '[month]/[day]/[year]' (read as UTC)
+        #[arg(long, value_name = "FORMAT")]
+        from_format: String,
+
+        /// Output format: iso, rfc3339, rfc2822, iso-basic, wom, or a `time` pattern
+        #[arg(long, value_name = "FORMAT", default_value = "iso")]
+        to_format: String,
+    },
+
+    /// Print the number of days in a month and whether its year is a leap year
+    #[command(after_help = "EXAMPLES\n  \
+                           et calinfo 2024-02\n    \
+                           29 days, leap year")]
+    Calinfo {
+        /// Month as YYYY-MM (e.g., 2024-02)
+        #[arg(value_name = "YYYY-MM", allow_hyphen_values = true)]
+        month: String,
+    },
+
+    /// Print midnight UTC on the Nth weekday of a month (e.g., the 3rd Monday)
+    #[command(after_help = "EXAMPLES\n  \
+                           et nth-weekday 2024 3 mon 3\n    \
+                           1710720000\n  \
+                           et nth-weekday 2024 3 fri -1\n    \
+                           1711670400")]
+    NthWeekday {
+        /// Year (e.g., 2024)
+        #[arg(allow_negative_numbers = true)]
+        year: i32,
+
+        /// Month number (1-12)
+        month: u8,
+
+        /// Day of the week, such as mon or monday
+        weekday: String,
+
+        /// Which occurrence: 1 for the first, -1 for the last
+        #[arg(allow_negative_numbers = true)]
+        n: i8,
+    },
+
+    /// Check that a timestamp parses, printing nothing and exiting 0 if it does
+    #[command(after_help = "EXAMPLES\n  \
+                           et validate 2024-01-10T18:45:45Z")]
+    Validate {
+        /// Epoch or ISO-8601 timestamp
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        input: String,
+
+        /// Only accept this kind of timestamp
+        #[arg(long, value_name = "KIND")]
+        require: Option<InputKind>,
+    },
+
+    /// Report whether a timestamp fits in 32-bit time and flag the Year 2038 boundary
+    #[command(after_help = "EXAMPLES\n  \
+                           et epoch-bits 2147483648\n    \
+                           i32: overflows\n    \
+                           u32: fits\n    \
+                           past the Year 2038 limit by 1s")]
+    EpochBits {
+        /// Epoch or ISO-8601 timestamp (default: now)
+        #[arg(value_name = "TIMESTAMP", allow_hyphen_values = true)]
+        timestamp: Option<String>,
+    },
+
+    /// Build an epoch timestamp from UTC calendar fields
+    #[command(after_help = "EXAMPLES\n  \
+                           et make --year 2024 --month 1 --day 10 --hour 12\n    \
+                           1704888000")]
+    Make {
+        #[arg(long, allow_negative_numbers = true)]
+        year: i32,
+
+        /// Month of the year (1-12)
+        #[arg(long, default_value_t = 1)]
+        month: u8,
+
+        #[arg(long, default_value_t = 1)]
+        day: u8,
+
+        #[arg(long, default_value_t = 0)]
+        hour: u8,
+
+        #[arg(long, default_value_t = 0)]
+        minute: u8,
+
+        #[arg(long, default_value_t = 0)]
+        second: u8,
+    },
+
+    /// Print the man page in roff format
+    #[command(hide = true)]
+    Manpage,
+
+    /// Print a shell completion script
+    #[command(hide = true)]
+    Completions {
+        /// bash, zsh, fish, elvish, or powershell
+        #[arg(value_name = "SHELL")]
+        shell: Shell,
     },
 }
 
 fn run() -> et::Result<()> {
-    let cli = Cli::parse();
+    let matches = Cli::command().get_matches();
+    check_flag_conflicts(&matches);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    let mut out = Output::new(&cli)?;
+    // Generating completions or the man page never needs the config, so a
+    // broken file should not stop it
+    let config = match cli.command {
+        Some(Command::Completions { .. } | Command::Manpage) => Config::default(),
+        _ => load_config()?,
+    };
+
+    // Without an assumed offset, naive ISO-8601 timestamps are rejected
+    let assume = match cli.assume_timezone.as_deref().map(str::trim) {
+        Some(offset) if !offset.is_empty() => Some(parse_offset(offset)?),
+        _ => config.timezone,
+    };
+    let iso = IsoInput { assume, lenient: cli.lenient, strict: cli.strict_iso };
+
+    // --now takes precedence over SOURCE_DATE_EPOCH, which takes precedence
+    // over the system clock. An empty SOURCE_DATE_EPOCH counts as unset.
+    let now = match cli.now_override.as_deref().map(str::trim) {
+        Some(epoch) if !epoch.is_empty() => iso.timestamp(epoch)?,
+        _ => now(),
+    };
+
+    // Terminate whatever was written, even if a later record failed
+    let result = dispatch(cli, now, iso, &config, &mut out);
+    out.finish()?;
+    result
+}
+
+fn dispatch(
+    cli: Cli,
+    now: i64,
+    iso: IsoInput,
+    config: &Config,
+    out: &mut Output,
+) -> et::Result<()> {
+    // --precision and ET_DEFAULT_PRECISION take precedence over the config
+    // file; an empty ET_DEFAULT_PRECISION counts as unset
+    let precision = match cli.precision.as_deref().map(str::trim) {
+        Some(unit) if !unit.is_empty() => unit.parse()?,
+        _ => config.precision.unwrap_or_default(),
+    };
+    // Every epoch read or printed counts in `precision` units
+    let at_precision = |seconds: i64| Timestamp::from_seconds(seconds).to_precision(precision);
+    let timestamp_or_now = |timestamp: Option<String>| match timestamp {
+        Some(t) => iso.timestamp_at(&t, precision),
+        None => at_precision(now),
+    };
 
     match cli.command {
         Some(Command::Now { duration }) => {
-            let epoch = now();
-            let result = match duration {
-                Some(d) => apply_duration(epoch, Duration::parse(&d)?)?,
-                None => epoch,
+            let epoch = Timestamp::from_seconds(now).to_precision(precision)?;
+            let duration = duration.as_deref().map(parse_duration).transpose()?;
+            out.line("now", epoch, duration)?;
+        }
+
+        Some(Command::Add { args }) => {
+            let now = Timestamp::from_seconds(now).to_precision(precision)?;
+            let (epoch, duration) = shift(&args, now, false)?;
+            out.line(shifted_input(&args), epoch, Some(duration))?;
+        }
+
+        Some(Command::Sub { args }) => {
+            let now = Timestamp::from_seconds(now).to_precision(precision)?;
+            let (epoch, duration) = shift(&args, now, true)?;
+            out.line(shifted_input(&args), epoch, Some(duration))?;
+        }
+
+        Some(Command::Parse { timestamp, input_format, local, round }) => {
+            let offset = if local { Some(local_offset()?) } else { iso.assume };
+            // ISO-8601 goes through its own parser to keep fractional seconds,
+            // --local, and --assume-timezone
+            let parse_iso = || {
+                let timestamp = iso.prepare(&timestamp)?;
+                match offset {
+                    Some(offset) => Timestamp::parse_iso_assuming(&timestamp, offset, Precision::Nanos),
+                    None => Timestamp::parse_iso(&timestamp, Precision::Nanos),
+                }
             };
-            println!("{result}");
+            let epoch = match input_format {
+                Some(InputFormat::Iso) => parse_iso()?,
+                // An epoch counts in --precision units, as `format` reads it
+                Some(format) => format.parse_timestamp(&iso.normalize(&timestamp), precision)?,
+                // --strict-iso turns off detecting the looser formats
+                None if iso.strict => Timestamp::parse_epoch(&timestamp, precision).or_else(|_| parse_iso())?,
+                None => Timestamp::parse_epoch(&timestamp, precision)
+                    .or_else(|_| parse_iso())
+                    .or_else(|_| parse_any_timestamp(&iso.normalize(&timestamp), Precision::Nanos))?,
+            };
+            let epoch = if round {
+                epoch.round_to(precision)?
+            } else {
+                epoch.to_precision(precision)?
+            };
+            out.timestamp(epoch)?;
+        }
+
+        Some(Command::Format { epoch, input, format, days_since_epoch: days }) => {
+            let epoch = match input {
+                Some(path) => read_timestamp(&path, |s| iso.timestamp_at(s, precision))?,
+                None => iso.timestamp_at(epoch.as_deref().unwrap_or_default(), precision)?,
+            };
+            // --format takes precedence over ET_FORMAT, then the config file; an
+            // empty ET_FORMAT counts as unset
+            let format = format
+                .or_else(|| std::env::var("ET_FORMAT").ok().filter(|f| !f.trim().is_empty()))
+                .or_else(|| config.format.clone());
+            let text = match (format, cli.output_tz) {
+                (format, Some(offset)) => {
+                    let format = format.as_deref().unwrap_or("iso");
+                    Some(epoch.format_with_at(format, parse_offset(&offset)?)?)
+                }
+                (Some(format), None) => Some(epoch.format_with(&format)?),
+                (None, None) => None,
+            };
+            if text.is_some() {
+                out.check_zero(epoch);
+            }
+            match text {
+                Some(text) if out.annotate => out.annotated(epoch, &text)?,
+                Some(text) => out.record(&text)?,
+                None if out.annotate => out.timestamp(epoch)?,
+                None => out.iso(epoch)?,
+            }
+            if days {
+                out.record(&days_since_epoch(epoch.as_seconds()?).to_string())?;
+            }
+        }
+
+        Some(Command::Components { epoch, json }) => {
+            let c = decompose(Timestamp::parse_epoch(&epoch, precision)?.as_seconds()?)?;
+            if json {
+                out.record(&c.to_json())?;
+            } else {
+                out.record(&format!("year: {}", c.year))?;
+                out.record(&format!("month: {}", c.month))?;
+                out.record(&format!("day: {}", c.day))?;
+                out.record(&format!("hour: {}", c.hour))?;
+                out.record(&format!("minute: {}", c.minute))?;
+                out.record(&format!("second: {}", c.second))?;
+                out.record(&format!("weekday: {}", weekday_name(c.weekday)))?;
+                out.record(&format!("day_of_year: {}", c.day_of_year))?;
+            }
+        }
+
+        Some(Command::Since { timestamp, human, abbrev, parts, relative_to }) => {
+            let epoch = iso.timestamp_at(&timestamp, precision)?;
+            let now = timestamp_or_now(relative_to)?;
+            let humanize = |epoch: Timestamp, now: Timestamp| -> et::Result<String> {
+                let (epoch, now) = (epoch.as_seconds()?, now.as_seconds()?);
+                Ok(humanize_relative_parts(epoch, now, parts.unwrap_or(1), abbrev))
+            };
+            if human {
+                out.record(&out.paint_relative(humanize(epoch, now)?))?;
+            } else {
+                out.record(&since(ticks(epoch)?, ticks(now)?)?.to_string())?;
+            }
+        }
+
+        Some(Command::Until { timestamp, human, abbrev, parts, relative_to }) => {
+            let epoch = iso.timestamp_at(&timestamp, precision)?;
+            let now = timestamp_or_now(relative_to)?;
+            let humanize = |epoch: Timestamp, now: Timestamp| -> et::Result<String> {
+                let (epoch, now) = (epoch.as_seconds()?, now.as_seconds()?);
+                Ok(humanize_relative_parts(epoch, now, parts.unwrap_or(1), abbrev))
+            };
+            if !human {
+                out.record(&until(ticks(epoch)?, ticks(now)?)?.to_string())?;
+            } else if epoch.value < now.value {
+                let ago = out.paint_relative(humanize(epoch, now)?);
+                out.record(&format!("already passed ({ago})"))?;
+            } else {
+                out.record(&out.paint_relative(humanize(epoch, now)?))?;
+            }
+        }
+
+        Some(Command::FmtDuration { seconds }) => {
+            let seconds = seconds
+                .trim()
+                .parse()
+                .map_err(|_| EtError::InvalidDuration(seconds.clone()))?;
+            out.record(&humanize_duration(seconds))?;
+        }
+
+        Some(Command::Between { from, to, step }) => {
+            let (from, to) = (iso.timestamp_at(&from, precision)?, iso.timestamp_at(&to, precision)?);
+            let step = parse_duration(&step)?;
+            // Fixed steps count in ticks so fractions of a second are not lost
+            let count = if step.is_fixed() {
+                count_steps(ticks(from)?, ticks(to)?, step_in_ticks(step, precision)?)?
+            } else {
+                count_steps(from.as_seconds()?, to.as_seconds()?, step)?
+            };
+            out.record(&count.to_string())?;
+        }
+
+        Some(Command::Seq { range, step, count_only: true }) => {
+            let range = EpochRange::parse_at(&range, precision)?;
+            let count = sequence_len(range, parse_duration(&step)?)?;
+            out.record(&count.to_string())?;
+        }
+
+        Some(Command::Seq { range, step, count_only: false }) => {
+            print_sequence(&range, &step, precision, out)?;
+        }
+
+        Some(Command::StartOf { period, timestamp, week_start }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            let week_start = week_start.or(config.week_start).unwrap_or_default();
+            out.timestamp(period_bounds(epoch, period, week_start, false)?.0)?;
+        }
+
+        Some(Command::EndOf { period, timestamp, week_start, exclusive_end }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            let week_start = week_start.or(config.week_start).unwrap_or_default();
+            out.timestamp(period_bounds(epoch, period, week_start, exclusive_end)?.1)?;
+        }
+
+        Some(Command::Range { period, timestamp, week_start, json, exclusive_end }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            let week_start = week_start.or(config.week_start).unwrap_or_default();
+            let (start, end) = period_bounds(epoch, period, week_start, exclusive_end)?;
+            let (start, end) = (start.value, end.value);
+            if json {
+                out.record(&format!("{{\"start\":{start},\"end\":{end}}}"))?;
+            } else {
+                out.record(&format!("{start}\t{end}"))?;
+            }
+        }
+
+        Some(Command::Week { timestamp }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            // ISO weeks always start on Monday, whatever the configured week start
+            let (start, end) = period_bounds(epoch, Period::Week, WeekStart::Monday, false)?;
+            let (year, week) = iso_week(epoch.as_seconds()?)?;
+            out.record(&format!("{}\t{}\t{year}-W{week:02}", start.value, end.value))?;
+        }
+
+        Some(Command::NextTime { time, from, offset }) => {
+            let from = timestamp_or_now(from)?;
+            let (time, offset) = time_of_day_args(&time, offset)?;
+            // The result is a whole second, so it is after `from` exactly when
+            // it is after `from`'s second
+            out.timestamp(at_precision(next_time_of_day(from.as_seconds()?, time, offset)?)?)?;
+        }
+
+        Some(Command::PrevTime { time, from, offset }) => {
+            let from = timestamp_or_now(from)?;
+            let (time, offset) = time_of_day_args(&time, offset)?;
+            // The result is a whole second, so it is before `from` exactly when
+            // it is before the first whole second at or after `from`
+            let ceiling = Timestamp::new(from.value + precision.per_second() - 1, precision);
+            out.timestamp(at_precision(prev_time_of_day(ceiling.as_seconds()?, time, offset)?)?)?;
+        }
+
+        Some(Command::Floor { timestamp, to }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            let rounded = floor_to(ticks(epoch)?, step_in_ticks(parse_duration(&to)?, precision)?)?;
+            out.timestamp(Timestamp::new(rounded.into(), precision))?;
         }
 
-        Some(Command::Parse { timestamp }) => {
-            let epoch = parse_iso(&timestamp)?;
-            println!("{epoch}");
+        Some(Command::Ceil { timestamp, to }) => {
+            let epoch = timestamp_or_now(timestamp)?;
+            let rounded = ceil_to(ticks(epoch)?, step_in_ticks(parse_duration(&to)?, precision)?)?;
+            out.timestamp(Timestamp::new(rounded.into(), precision))?;
         }
 
-        Some(Command::Format { epoch }) => {
-            let epoch_val = parse_epoch(&epoch)?;
-            let iso = format_iso(epoch_val)?;
-            println!("{iso}");
+        Some(Command::TzOffset { from, to, timestamp, seconds }) => {
+            let (from, to) = (parse_offset(&from)?, parse_offset(&to)?);
+            let epoch = match timestamp {
+                Some(t) => IsoInput { assume: Some(from), ..iso }.timestamp_at(&t, precision)?.as_seconds()?,
+                None => now,
+            };
+            let difference = to.whole_seconds() as i64 - from.whole_seconds() as i64;
+            out.record(&format_iso_at(epoch, to)?)?;
+            if seconds {
+                out.record(&difference.to_string())?;
+            } else {
+                out.record(&humanize_duration(difference))?;
+            }
+        }
+
+        Some(Command::OffsetNow { seconds }) => {
+            let offset = local_offset()?;
+            if seconds {
+                out.record(&offset.whole_seconds().to_string())?;
+            } else {
+                out.record(&format_offset(offset))?;
+            }
+        }
+
+        Some(Command::Random { from, to, count, seed }) => {
+            let from = ticks(iso.timestamp_at(&from, precision)?)?;
+            let to = ticks(iso.timestamp_at(&to, precision)?)?;
+            let seed = seed.unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos() as u64)
+            });
+            for epoch in RandomEpochs::new(from, to, seed)?.take(count) {
+                out.timestamp(Timestamp::new(epoch.into(), precision))?;
+            }
+        }
+
+        Some(Command::Apply) => {
+            let stdin = StdinOptions {
+                no_fallback: cli.no_fallback,
+                max_lines: cli.max_lines,
+                auto_precision: cli.auto_precision,
+                from_iso: cli.from_iso.then_some(iso),
+                skip_errors: cli.skip_errors,
+            };
+            apply_stdin_pairs(precision, stdin, out)?;
+        }
+
+        Some(Command::Transcode { from_format, to_format }) => {
+            let stdin = StdinOptions {
+                no_fallback: cli.no_fallback,
+                max_lines: cli.max_lines,
+                auto_precision: cli.auto_precision,
+                from_iso: cli.from_iso.then_some(iso),
+                skip_errors: cli.skip_errors,
+            };
+            transcode_stdin(&from_format, &to_format, stdin, out)?;
+        }
+
+        Some(Command::Calinfo { month }) => {
+            let invalid = || EtError::InvalidDate(format!("{month} is not YYYY-MM"));
+            let (year, number) = month.trim().rsplit_once('-').ok_or_else(invalid)?;
+            let year: i32 = year.parse().map_err(|_| invalid())?;
+            let days = days_in_month(year, number.parse().map_err(|_| invalid())?)?;
+            let leap = if is_leap_year(year) { "leap year" } else { "not a leap year" };
+            out.record(&format!("{days} days, {leap}"))?;
+        }
+
+        Some(Command::NthWeekday { year, month, weekday, n }) => {
+            out.timestamp(at_precision(nth_weekday(year, month, parse_weekday(&weekday)?, n)?)?)?;
+        }
+
+        Some(Command::Validate { input, require }) => {
+            // Parsing is the whole check; an error sets the exit code
+            match require {
+                Some(InputKind::Epoch) => Timestamp::parse_epoch(&input, precision)?.as_seconds()?,
+                Some(InputKind::Iso) => iso.iso(&input)?,
+                None => iso.timestamp_at(&input, precision)?.as_seconds()?,
+            };
+        }
+
+        Some(Command::EpochBits { timestamp }) => {
+            // 32-bit time counts seconds, whatever the precision
+            let epoch = timestamp_or_now(timestamp)?.as_seconds()?;
+            let fits = |ok: bool| if ok { "fits" } else { "overflows" };
+            out.record(&format!("i32: {}", fits(fits_in_i32(epoch))))?;
+            out.record(&format!("u32: {}", fits(fits_in_u32(epoch))))?;
+            let last = i64::from(i32::MAX);
+            match epoch.cmp(&last) {
+                Ordering::Equal => out.record("last second representable in signed 32-bit time")?,
+                Ordering::Greater => out.record(&format!(
+                    "past the Year 2038 limit by {}",
+                    humanize_duration(epoch - last)
+                ))?,
+                Ordering::Less => {}
+            }
+        }
+
+        Some(Command::Make { year, month, day, hour, minute, second }) => {
+            let epoch = compose(year, month, day, hour, minute, second)?;
+            out.timestamp(at_precision(epoch)?)?;
+        }
+
+        Some(Command::Manpage) => {
+            let mut page = Vec::new();
+            clap_mangen::Man::new(Cli::command()).render(&mut page)?;
+            out.stdout.write_all(&page)?;
+        }
+
+        Some(Command::Completions { shell }) => {
+            // Generate into a buffer: clap_complete panics on write errors
+            // such as a closed pipe
+            let mut script = Vec::new();
+            clap_complete::generate(shell, &mut Cli::command(), "et", &mut script);
+            out.stdout.write_all(&script)?;
         }
 
         None => {
             // Handle positional arguments or stdin
-            handle_args_or_stdin(&cli.args)?;
+            let now = Timestamp::from_seconds(now).to_precision(precision)?;
+            let stdin = StdinOptions {
+                no_fallback: cli.no_fallback,
+                max_lines: cli.max_lines,
+                auto_precision: cli.auto_precision,
+                from_iso: cli.from_iso.then_some(iso),
+                skip_errors: cli.skip_errors,
+            };
+            // A DURATION argument overrides ET_DURATION; an empty value counts as unset
+            let default_duration = std::env::var("ET_DURATION").ok().filter(|d| !d.trim().is_empty());
+            let args = (cli.arg.as_deref(), cli.duration.as_deref());
+            if cli.dry_run {
+                return check_durations(args, precision, out);
+            }
+            if let Some(step) = &cli.group_by {
+                return group_stdin(parse_duration(step)?, precision, stdin, out);
+            }
+            handle_args_or_stdin(args, default_duration.as_deref(), now, stdin, out)?;
         }
     }
 
     Ok(())
 }
 
-fn handle_args_or_stdin(args: &[String]) -> et::Result<()> {
-    match args.len() {
-        0 => {
+/// Reject flag combinations that `conflicts_with` and `requires` cannot
+/// express, with a usage error naming the flags.
+///
+/// `--local` and `--assume-timezone` given together would each decide the
+/// same thing. This is not `conflicts_with`, which would also count
+/// `ET_ASSUME_TIMEZONE` from the environment; an explicit `--local` should
+/// simply override that. `--output-tz` needs `--iso` everywhere but `format`,
+/// which always prints a date.
+fn check_flag_conflicts(matches: &ArgMatches) {
+    let (name, sub) = matches.subcommand().unwrap_or(("", matches));
+    let given = |id| sub.value_source(id) == Some(ValueSource::CommandLine);
+    if name == "parse" && given("local") && given("assume_timezone") {
+        let message = "--local and --assume-timezone both set the timezone for input without one; pass only one";
+        Cli::command().error(ErrorKind::ArgumentConflict, message).exit();
+    }
+    if name != "format" && given("output_tz") && !given("iso") {
+        let message = "--output-tz sets the offset of ISO-8601 output; pass --iso too";
+        Cli::command().error(ErrorKind::MissingRequiredArgument, message).exit();
+    }
+}
+
+/// Handle the positional `[ARG] [DURATION]` form. `default_duration`
+/// (from `ET_DURATION`) stands in for a missing DURATION, unless ARG is
+/// itself a duration or a range.
+fn handle_args_or_stdin(
+    (arg, duration): (Option<&str>, Option<&str>),
+    default_duration: Option<&str>,
+    now: Timestamp,
+    stdin: StdinOptions,
+    out: &mut Output,
+) -> et::Result<()> {
+    let precision = now.precision;
+    let duration = match (arg, duration) {
+        (_, Some(duration)) => Some(duration),
+        (Some(arg), None) if is_range(arg) || is_duration_list(arg) => None,
+        _ => default_duration,
+    };
+
+    match (arg, duration) {
+        (None, None) => {
             // No args - try stdin, fall back to now
-            if try_process_stdin(None)? == 0 {
-                println!("{}", now());
+            if try_process_stdin(None, precision, stdin, out)? == 0 {
+                out.line("now", now, None)?;
             }
         }
-        1 => {
-            let arg = &args[0];
-
-            if arg == "now" {
+        (None, Some(duration)) => {
+            // Only ET_DURATION - apply it to stdin, falling back to now
+            let duration = parse_duration(duration)?;
+            if try_process_stdin(Some(duration), precision, stdin, out)? == 0 {
+                out.line("now", now, Some(duration))?;
+            }
+        }
+        (Some(arg), None) => {
+            if is_range(arg) {
+                // Ranges can hold a huge number of timestamps, so never guess a step
+                return Err(EtError::InvalidRange(format!(
+                    "{arg} needs a step, e.g. `et {arg} 1d`"
+                )));
+            } else if arg == "now" {
                 // `et now` - print current time
-                println!("{}", now());
-            } else if is_duration(arg) {
-                let duration = Duration::parse(arg)?;
+                out.line(arg, now, None)?;
+            } else if is_duration_list(arg) {
+                let duration = parse_duration(arg)?;
                 // Try stdin first; if no data, apply to now
-                if try_process_stdin(Some(duration))? == 0 {
-                    let result = apply_duration(now(), duration)?;
-                    println!("{result}");
+                if try_process_stdin(Some(duration), precision, stdin, out)? == 0 {
+                    out.line("now", now, Some(duration))?;
                 }
             } else {
                 // `et 1704912345` - just echo the epoch
-                let epoch = parse_epoch(arg)?;
-                println!("{epoch}");
+                out.line(arg, Timestamp::parse_epoch(arg, precision)?, None)?;
             }
         }
-        2 => {
+        (Some(arg), Some(step)) if is_range(arg) => {
+            // et START..END STEP - shorthand for `et seq START..END --step STEP`
+            print_sequence(arg, step, now.precision, out)?;
+        }
+        (Some(arg), Some(duration)) => {
             // et EPOCH DURATION or et now DURATION
-            let epoch = if args[0] == "now" {
-                now()
+            let epoch = if arg == "now" {
+                now
             } else {
-                parse_epoch(&args[0])?
+                Timestamp::parse_epoch(arg, precision)?
             };
-            let duration = Duration::parse(&args[1])?;
-            let result = apply_duration(epoch, duration)?;
-            println!("{result}");
+            let duration = parse_duration(duration)?;
+            out.line(arg, epoch, Some(duration))?;
         }
-        _ => {
-            return Err(EtError::InvalidDuration("too many arguments".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Read defaults from `$XDG_CONFIG_HOME/et/config.toml`, or
+/// `~/.config/et/config.toml` when XDG_CONFIG_HOME is unset. A missing file
+/// means no defaults.
+fn load_config() -> et::Result<Config> {
+    let dir = match std::env::var_os("XDG_CONFIG_HOME").filter(|dir| !dir.is_empty()) {
+        Some(dir) => PathBuf::from(dir),
+        None => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".config"),
+            None => return Ok(Config::default()),
+        },
+    };
+
+    match std::fs::read_to_string(dir.join("et").join("config.toml")) {
+        Ok(text) => Config::parse(&text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Parse the contents of a file as a single timestamp, ignoring surrounding
+/// whitespace. Errors name the file.
+fn read_timestamp(path: &Path, parse: impl Fn(&str) -> et::Result<Timestamp>) -> et::Result<Timestamp> {
+    let in_file = |e: EtError| EtError::InFile(path.to_path_buf(), e.to_string());
+    let text = std::fs::read_to_string(path).map_err(|e| in_file(e.into()))?;
+    parse(text.trim()).map_err(in_file)
+}
+
+/// How ISO-8601 input is read, as set by the global flags.
+#[derive(Clone, Copy, Debug)]
+struct IsoInput {
+    /// Offset for timestamps without a timezone (`--assume-timezone`)
+    assume: Option<UtcOffset>,
+    /// Accept lowercase `t` and `z` (`--lenient`)
+    lenient: bool,
+    /// Require the strict RFC 3339 shape (`--strict-iso`)
+    strict: bool,
+}
+
+impl IsoInput {
+    /// Parse an epoch or ISO-8601 timestamp.
+    fn timestamp(&self, s: &str) -> et::Result<i64> {
+        parse_epoch(s).or_else(|_| self.iso(s))
+    }
+
+    /// Parse an ISO-8601 timestamp only, never an epoch.
+    fn iso(&self, s: &str) -> et::Result<i64> {
+        let s = self.prepare(s)?;
+        match self.assume {
+            Some(offset) => parse_iso_assuming(&s, offset),
+            None => parse_iso(&s),
+        }
+    }
+
+    /// Parse an epoch at `precision`, or an ISO-8601 timestamp keeping
+    /// fractional seconds down to `precision`.
+    fn timestamp_at(&self, s: &str, precision: Precision) -> et::Result<Timestamp> {
+        Timestamp::parse_epoch(s, precision).or_else(|_| self.iso_at(s, precision))
+    }
+
+    /// Parse an ISO-8601 timestamp only, keeping fractional seconds down to
+    /// `precision`.
+    fn iso_at(&self, s: &str, precision: Precision) -> et::Result<Timestamp> {
+        let s = self.prepare(s)?;
+        match self.assume {
+            Some(offset) => Timestamp::parse_iso_assuming(&s, offset, precision),
+            None => Timestamp::parse_iso(&s, precision),
         }
     }
 
+    /// Get ISO-8601 input ready to parse: fix the case of its separators
+    /// when lenient, and check its shape when strict.
+    fn prepare<'a>(&self, s: &'a str) -> et::Result<Cow<'a, str>> {
+        if self.strict {
+            check_strict_iso(s)?;
+        }
+        Ok(self.normalize(s))
+    }
+
+    /// Fix the case of ISO-8601 separators when lenient.
+    fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.lenient {
+            Cow::Owned(normalize_iso(s))
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+}
+
+/// Read the arguments of `next-time` and `prev-time`: the time of day and
+/// the offset it is read at (default: UTC).
+fn time_of_day_args(time: &str, offset: Option<String>) -> et::Result<(Time, UtcOffset)> {
+    let offset = match offset {
+        Some(offset) => parse_offset(&offset)?,
+        None => UtcOffset::UTC,
+    };
+    Ok((parse_time_of_day(time)?, offset))
+}
+
+/// Split a duration argument into tokens separated by commas or spaces.
+fn duration_tokens(s: &str) -> Vec<&str> {
+    s.split([',', ' ']).filter(|t| !t.is_empty()).collect()
+}
+
+/// Whether every token of a duration argument looks like a duration.
+fn is_duration_list(s: &str) -> bool {
+    let tokens = duration_tokens(s);
+    !tokens.is_empty() && tokens.iter().all(|t| is_duration(t))
+}
+
+/// Parse a duration argument such as `+3h` or a list that sums, such as
+/// `1h,30m` or `"1h 30m"`.
+fn parse_duration(s: &str) -> et::Result<Duration> {
+    Duration::parse_sum(&duration_tokens(s))
+}
+
+/// Parse every duration in the positional arguments for `--dry-run` and
+/// print each in normalized form, printing nothing if any fails. ARG counts
+/// as a duration unless it is an epoch, `now`, or a range. Durations are
+/// numbered from 1 across both arguments, so `et 1h,3x` fails at duration 2.
+fn check_durations(
+    (arg, duration): (Option<&str>, Option<&str>),
+    precision: Precision,
+    out: &mut Output,
+) -> et::Result<()> {
+    let arg = arg.filter(|a| *a != "now" && !is_range(a) && Timestamp::parse_epoch(a, precision).is_err());
+    let durations = arg
+        .into_iter()
+        .chain(duration)
+        .flat_map(duration_tokens)
+        .enumerate()
+        .map(|(i, token)| Duration::parse(token).map_err(|e| EtError::InvalidDurationAt(i + 1, e.to_string())))
+        .collect::<et::Result<Vec<_>>>()?;
+    for duration in durations {
+        out.record(&duration.to_string())?;
+    }
+    Ok(())
+}
+
+/// The timestamp `add` and `sub` start from, as given: EPOCH, or `now`
+/// when it is left out.
+fn shifted_input(args: &[String]) -> &str {
+    match args {
+        [epoch, _] => epoch,
+        _ => "now",
+    }
+}
+
+/// Read the epoch and duration from `[EPOCH] DURATION` arguments, negating
+/// the duration for `sub`. A missing epoch defaults to `now`.
+fn shift(args: &[String], now: Timestamp, negate: bool) -> et::Result<(Timestamp, Duration)> {
+    let (epoch, duration) = match args {
+        [duration] => (now, duration),
+        [epoch, duration] if epoch == "now" => (now, duration),
+        [epoch, duration] => (Timestamp::parse_epoch(epoch, now.precision)?, duration),
+        _ => unreachable!("clap accepts one or two arguments"),
+    };
+
+    let duration = parse_duration(duration)?;
+    let duration = if negate { duration.negate()? } else { duration };
+    Ok((epoch, duration))
+}
+
+/// Print every timestamp in `range`, `step` apart. A `--template` sees the
+/// range as each timestamp's input.
+fn print_sequence(range: &str, step: &str, precision: Precision, out: &mut Output) -> et::Result<()> {
+    let parsed = EpochRange::parse_at(range, precision)?;
+    let step = parse_duration(step)?;
+    for epoch in sequence(parsed, step)? {
+        out.line(range, Timestamp::from_seconds(epoch?).to_precision(precision)?, None)?;
+    }
     Ok(())
 }
 
+/// An epoch's value as an `i64` count of its own ticks, for arithmetic that
+/// works the same at any precision.
+fn ticks(epoch: Timestamp) -> et::Result<i64> {
+    i64::try_from(epoch.value).map_err(|_| EtError::Overflow)
+}
+
+/// `step` counted in ticks of `precision` rather than seconds, so a fixed
+/// step applies to `ticks` directly. Calendar steps are left as they are.
+fn step_in_ticks(step: Duration, precision: Precision) -> et::Result<Duration> {
+    if step.is_calendar() {
+        return Ok(step);
+    }
+    step.scale(precision.per_second() as i32)
+}
+
+/// The start and end of the period containing `epoch`, at its precision.
+/// An inclusive end is the last tick before the next period, such as
+/// 23:59:59.999 in milliseconds; an exclusive end is the next period's start.
+fn period_bounds(
+    epoch: Timestamp,
+    period: Period,
+    week_start: WeekStart,
+    exclusive_end: bool,
+) -> et::Result<(Timestamp, Timestamp)> {
+    let seconds = epoch.as_seconds()?;
+    let at_precision = |seconds| Timestamp::from_seconds(seconds).to_precision(epoch.precision);
+    let start = at_precision(start_of(seconds, period, week_start)?)?;
+    let next = at_precision(next_start_of(seconds, period, week_start)?)?;
+    let end = if exclusive_end { next } else { Timestamp::new(next.value - 1, next.precision) };
+    Ok((start, end))
+}
+
+/// Limits on reading timestamps from stdin, and how to print each line.
+#[derive(Clone, Debug)]
+struct StdinOptions {
+    /// Treat piped but empty stdin as an error instead of falling back to now
+    no_fallback: bool,
+    /// Stop with an error once this many timestamps have been processed
+    max_lines: Option<usize>,
+    /// Guess each line's precision from its digit count (`--auto-precision`)
+    auto_precision: bool,
+    /// Read each line as ISO-8601 this way instead of as an epoch (`--from-iso`)
+    from_iso: Option<IsoInput>,
+    /// Report bad lines on stderr and keep going (`--skip-errors`)
+    skip_errors: bool,
+}
+
+impl StdinOptions {
+    /// Parse an epoch from stdin at `precision`, or at its guessed precision
+    /// converted to `precision` with `--auto-precision`. With `--from-iso`,
+    /// the line is an ISO-8601 timestamp instead.
+    fn epoch(&self, s: &str, precision: Precision) -> et::Result<Timestamp> {
+        if let Some(iso) = &self.from_iso {
+            iso.iso_at(s, precision)
+        } else if self.auto_precision {
+            Timestamp::parse_epoch(s, Precision::guess(s))?.to_precision(precision)
+        } else {
+            Timestamp::parse_epoch(s, precision)
+        }
+    }
+
+    /// Stop on the error from line number `line`, or with `--skip-errors`
+    /// report it on stderr and carry on. Only errors in reading or computing
+    /// a line belong here; errors writing the output always stop.
+    fn skip_line(&self, line: usize, e: EtError) -> et::Result<()> {
+        let e = EtError::InvalidLine(line, e.to_string());
+        if !self.skip_errors {
+            return Err(e);
+        }
+        eprintln!("error: {e}");
+        Ok(())
+    }
+}
+
+/// A `--template` for results: literal text with `{input}`, `{result}`,
+/// and `{iso}` placeholders. `{{` and `}}` stand for literal braces.
+#[derive(Clone, Debug)]
+struct Template(Vec<Segment>);
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Text(String),
+    Input,
+    Result,
+    Iso,
+}
+
+impl FromStr for Template {
+    type Err = EtError;
+
+    fn from_str(s: &str) -> et::Result<Self> {
+        let invalid = |why: String| EtError::InvalidFormat(format!("template {s:?}: {why}"));
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut rest = s;
+
+        while let Some(c) = rest.chars().next() {
+            rest = &rest[c.len_utf8()..];
+            match c {
+                '{' | '}' if rest.starts_with(c) => {
+                    text.push(c);
+                    rest = &rest[1..];
+                }
+                '{' => {
+                    let (name, after) = rest
+                        .split_once('}')
+                        .ok_or_else(|| invalid("unclosed {".to_string()))?;
+                    let segment = match name {
+                        "input" => Segment::Input,
+                        "result" => Segment::Result,
+                        "iso" => Segment::Iso,
+                        other => return Err(invalid(format!("unknown placeholder {{{other}}}"))),
+                    };
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(segment);
+                    rest = after;
+                }
+                '}' => return Err(invalid("unmatched } (write }} for a literal brace)".to_string())),
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+
+        Ok(Template(segments))
+    }
+}
+
+impl Template {
+    /// Write one line to `w` with the placeholders filled in, padding the
+    /// result to `pad` digits if given.
+    fn write(
+        &self,
+        w: &mut impl Write,
+        input: &str,
+        result: Timestamp,
+        pad: Option<usize>,
+    ) -> et::Result<()> {
+        for segment in &self.0 {
+            match segment {
+                Segment::Text(text) => w.write_all(text.as_bytes())?,
+                Segment::Input => w.write_all(input.as_bytes())?,
+                Segment::Result => write_padded(w, result, pad)?,
+                Segment::Iso => result.write_iso(w)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write an epoch in decimal to `w`, zero-padded to `pad` digits if given.
+fn write_padded(w: &mut impl Write, ts: Timestamp, pad: Option<usize>) -> io::Result<()> {
+    match pad {
+        Some(width) => write!(w, "{:0width$}", ts.value),
+        None => write!(w, "{}", ts.value),
+    }
+}
+
 /// Try to process timestamps from stdin. Returns the number of lines processed.
-/// Returns 0 if stdin is a terminal or has no data (allowing caller to fall back).
-fn try_process_stdin(duration: Option<Duration>) -> et::Result<usize> {
+/// Returns 0 if stdin is a terminal or has no data (allowing caller to fall back),
+/// unless `no_fallback` is set, in which case a piped but empty stdin is an error.
+fn try_process_stdin(
+    duration: Option<Duration>,
+    precision: Precision,
+    options: StdinOptions,
+    out: &mut Output,
+) -> et::Result<usize> {
     let stdin = io::stdin();
 
     // If stdin is a terminal, no data to read
@@ -154,11 +1826,87 @@ fn try_process_stdin(duration: Option<Duration>) -> et::Result<usize> {
         return Ok(0);
     }
 
-    let stdout = io::stdout();
-    let mut stdout_lock = stdout.lock();
+    for_each_line(stdin.lock(), &options, out, |line, out| {
+        let epoch = options.epoch(line, precision)?;
+        out.line(line, epoch, duration)
+    })
+}
+
+/// Count stdin timestamps per `step` for `--group-by` and print
+/// `BUCKET COUNT` lines in bucket order. Buckets start at multiples of
+/// `step` since the epoch, as `floor_to` rounds.
+fn group_stdin(
+    step: Duration,
+    precision: Precision,
+    options: StdinOptions,
+    out: &mut Output,
+) -> et::Result<()> {
+    // Reject a calendar or non-positive step even when stdin is empty
+    floor_to(0, step)?;
+    let stdin = io::stdin();
+    let mut buckets = BTreeMap::new();
+
+    if !stdin.is_terminal() {
+        for_each_line(stdin.lock(), &options, out, |line, _| {
+            let bucket = floor_to(options.epoch(line, precision)?.as_seconds()?, step)?;
+            *buckets.entry(bucket).or_insert(0) += 1;
+            Ok(())
+        })?;
+    }
+
+    for (bucket, n) in buckets {
+        let bucket = Timestamp::from_seconds(bucket).to_precision(precision)?;
+        out.separate()?;
+        write_padded(&mut out.stdout, bucket, out.pad)?;
+        write!(out.stdout, " {n}")?;
+    }
+    Ok(())
+}
+
+/// Rewrite each line of stdin from `from` to `to`, parsing with `parse_with`
+/// and printing with `format_with`.
+fn transcode_stdin(from: &str, to: &str, options: StdinOptions, out: &mut Output) -> et::Result<()> {
+    for_each_line(io::stdin().lock(), &options, out, |line, out| {
+        let text = format_with(parse_with(line, from)?, to)?;
+        out.record(&text)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Apply per-line durations from `EPOCH DURATION` lines on stdin.
+fn apply_stdin_pairs(
+    precision: Precision,
+    options: StdinOptions,
+    out: &mut Output,
+) -> et::Result<()> {
+    for_each_line(io::stdin().lock(), &options, out, |line, out| {
+        let [epoch, duration] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(EtError::InvalidFormat(format!("expected EPOCH DURATION, got {line:?}")));
+        };
+        let epoch = options.epoch(epoch, precision)?;
+        let duration = parse_duration(duration)?;
+        out.line(line, epoch, Some(duration))
+    })?;
+    Ok(())
+}
+
+/// Run `each` on every non-blank line of `input`, trimmed, letting it write
+/// its results to `out`. Returns the number of lines read.
+///
+/// Reading stops with an error past `--max-lines`, and with `--no-fallback`
+/// when there were no lines. A line that `each` fails on stops it too, or
+/// with `--skip-errors` is reported on stderr and skipped; errors writing
+/// the output always stop.
+fn for_each_line(
+    input: impl BufRead,
+    options: &StdinOptions,
+    out: &mut Output,
+    mut each: impl FnMut(&str, &mut Output) -> et::Result<()>,
+) -> et::Result<usize> {
     let mut count = 0;
 
-    for line in stdin.lock().lines() {
+    for (index, line) in input.lines().enumerate() {
         let line = line?;
         let trimmed = line.trim();
 
@@ -167,15 +1915,22 @@ fn try_process_stdin(duration: Option<Duration>) -> et::Result<usize> {
             continue;
         }
 
+        // Everything up to the limit has been written; report the rest
+        if options.max_lines.is_some_and(|max| count >= max) {
+            return Err(EtError::TooManyLines(count));
+        }
+
         count += 1;
 
-        let epoch = parse_epoch(trimmed)?;
-        let result = match duration {
-            Some(d) => apply_duration(epoch, d)?,
-            None => epoch,
-        };
+        match each(trimmed, out) {
+            Ok(()) => {}
+            Err(e @ EtError::Io(_)) => return Err(e),
+            Err(e) => options.skip_line(index + 1, e)?,
+        }
+    }
 
-        writeln!(stdout_lock, "{result}")?;
+    if count == 0 && options.no_fallback {
+        return Err(EtError::NoInput);
     }
 
     Ok(count)
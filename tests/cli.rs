@@ -0,0 +1,1273 @@
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Build a command for the `et` binary with the given arguments, isolated
+/// from environment variables and config files that change its behavior.
+fn et_command(args: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_et"));
+    command
+        .args(args)
+        .env_remove("SOURCE_DATE_EPOCH")
+        .env_remove("ET_ASSUME_TIMEZONE")
+        .env_remove("ET_DEFAULT_PRECISION")
+        .env_remove("ET_FORMAT")
+        .env_remove("ET_DURATION")
+        .env("XDG_CONFIG_HOME", concat!(env!("CARGO_TARGET_TMPDIR"), "/no-config"));
+    command
+}
+
+/// Run the `et` binary with the given arguments and an empty stdin.
+fn et(args: &[&str]) -> Output {
+    et_command(args)
+        .stdin(Stdio::null())
+        .output()
+        .expect("failed to run et")
+}
+
+/// Run the `et` binary with the given arguments, feeding `input` on stdin.
+fn et_with_stdin(args: &[&str], input: &str) -> Output {
+    let mut child = et_command(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run et");
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Run `et` and return its stdout, asserting it exited successfully.
+fn stdout(args: &[&str]) -> String {
+    let output = et(args);
+    assert!(output.status.success(), "et {args:?} failed: {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// Bare Epochs
+#[test]
+fn bare_epoch_is_echoed_unless_iso_is_given() {
+    assert_eq!(stdout(&["1704912345"]), "1704912345\n");
+    assert_eq!(stdout(&["1704912345", "--iso"]), "2024-01-10T18:45:45Z\n");
+    assert_eq!(stdout(&["--precision", "ms", "--iso", "1704912345123"]), "2024-01-10T18:45:45.123Z\n");
+}
+
+#[test]
+fn iso_formats_results_with_durations() {
+    assert_eq!(stdout(&["1704912345", "+1h", "--iso"]), "2024-01-10T19:45:45Z\n");
+    assert_eq!(stdout(&["--now", "1704912345", "--iso", "-1d"]), "2024-01-09T18:45:45Z\n");
+    assert_eq!(stdout(&["--iso", "0..=3600", "1h"]), "1970-01-01T00:00:00Z\n1970-01-01T01:00:00Z\n");
+    let output = et_command(&["1704912345", "--iso"])
+        .env("ET_DURATION", "+1h")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2024-01-10T19:45:45Z\n");
+    assert!(!et(&["1704912345", "+1h", "--iso", "--explain"]).status.success());
+}
+
+// Padding
+#[test]
+fn pad_flag_pads_numeric_output() {
+    assert_eq!(stdout(&["42", "--pad", "8"]), "00000042\n");
+    assert_eq!(stdout(&["--pad", "12", "1704912345"]), "001704912345\n");
+    assert_eq!(stdout(&["0", "-42", "--pad", "6"]), "-00042\n");
+}
+
+#[test]
+fn pad_flag_leaves_iso_output_alone() {
+    assert_eq!(stdout(&["format", "0", "--pad", "30"]), "1970-01-01T00:00:00Z\n");
+}
+
+// Trailing Newline
+#[test]
+fn no_newline_suppresses_final_newline() {
+    assert_eq!(stdout(&["-n", "1704888000"]), "1704888000");
+    assert_eq!(stdout(&["1704888000", "--no-newline"]), "1704888000");
+}
+
+#[test]
+fn no_newline_only_affects_last_record() {
+    let output = et_with_stdin(&["-n"], "1\n2\n3\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n2\n3");
+}
+
+// Separators
+#[test]
+fn separator_joins_stdin_results() {
+    let output = et_with_stdin(&["+1s", "--separator", ","], "0\n10\n20\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1,11,21\n");
+}
+
+#[test]
+fn oneline_joins_with_spaces() {
+    let output = et_with_stdin(&["--oneline"], "1\n2\n3\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1 2 3\n");
+}
+
+#[test]
+fn separator_joins_components() {
+    assert_eq!(
+        stdout(&["components", "0", "--separator", ";"]),
+        "year: 1970;month: 1;day: 1;hour: 0;minute: 0;second: 0;weekday: Thursday;day_of_year: 1\n"
+    );
+}
+
+#[test]
+fn default_separator_is_newline() {
+    let output = et_with_stdin(&[], "1\n2\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n2\n");
+}
+
+#[test]
+fn separator_joins_seq_range() {
+    assert_eq!(stdout(&["seq", "0..=3", "--step", "1s", "--separator", ","]), "0,1,2,3\n");
+}
+
+// Ranges
+#[test]
+fn range_shorthand_with_step() {
+    assert_eq!(stdout(&["0..=172800", "1d"]), "0\n86400\n172800\n");
+    assert_eq!(stdout(&["seq", "0..172800", "--step", "1d"]), "0\n86400\n");
+}
+
+#[test]
+fn range_without_step_is_an_error() {
+    let output = et(&["0..172800"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("needs a step"));
+}
+
+// Reference Time
+#[test]
+fn now_flag_fixes_current_time() {
+    assert_eq!(stdout(&["--now", "1704888000"]), "1704888000\n");
+    assert_eq!(stdout(&["now", "+1h", "--now", "1704888000"]), "1704891600\n");
+    assert_eq!(stdout(&["--now", "2024-01-10T12:00:00Z", "-1d"]), "1704801600\n");
+}
+
+#[test]
+fn source_date_epoch_makes_now_deterministic() {
+    let output = et_command(&["now"])
+        .env("SOURCE_DATE_EPOCH", "1704888000")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000\n");
+}
+
+#[test]
+fn now_flag_overrides_source_date_epoch() {
+    let output = et_command(&["now", "--now", "42"])
+        .env("SOURCE_DATE_EPOCH", "1704888000")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "42\n");
+}
+
+#[test]
+fn empty_source_date_epoch_uses_clock() {
+    let output = et_command(&["now"])
+        .env("SOURCE_DATE_EPOCH", "")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let epoch: i64 = String::from_utf8(output.stdout).unwrap().trim().parse().unwrap();
+    assert!(epoch > 1704888000);
+}
+
+// Precision
+#[test]
+fn precision_applies_to_input_and_output() {
+    assert_eq!(stdout(&["--precision", "ms", "1704888000123", "+1h"]), "1704891600123\n");
+    assert_eq!(stdout(&["--precision", "ms", "--now", "1", "now"]), "1000\n");
+    assert_eq!(
+        stdout(&["--precision", "us", "format", "1704888000123456"]),
+        "2024-01-10T12:00:00.123456Z\n"
+    );
+}
+
+#[test]
+fn precision_applies_to_periods_sequences_and_make() {
+    let ms = |args: &[&str]| stdout(&[&["--precision", "ms"], args].concat());
+    assert_eq!(ms(&["start-of", "day", "1704912345123"]), "1704844800000\n");
+    // An inclusive end is the last millisecond of the day
+    assert_eq!(ms(&["end-of", "day", "1704912345123"]), "1704931199999\n");
+    assert_eq!(ms(&["end-of", "day", "1704912345123", "--exclusive-end"]), "1704931200000\n");
+    assert_eq!(ms(&["range", "day", "1704912345123"]), "1704844800000\t1704931199999\n");
+    assert_eq!(ms(&["seq", "0..=2000", "--step", "1s"]), "0\n1000\n2000\n");
+    assert_eq!(ms(&["seq", "0..2000", "--step", "1s", "--count-only"]), "2\n");
+    assert_eq!(ms(&["make", "--year", "1970", "--second", "1"]), "1000\n");
+}
+
+#[test]
+fn precision_applies_to_rounding_and_differences() {
+    let ms = |args: &[&str]| stdout(&[&["--precision", "ms"], args].concat());
+    assert_eq!(ms(&["floor", "1704912345123", "--to", "15m"]), "1704912300000\n");
+    assert_eq!(ms(&["ceil", "1704912345123", "--to", "15m"]), "1704913200000\n");
+    assert_eq!(ms(&["since", "1704912345123", "--relative-to", "1704916000000"]), "3654877\n");
+    assert_eq!(ms(&["until", "1704916000000", "--relative-to", "1704912345123"]), "3654877\n");
+    assert_eq!(ms(&["week", "1704912345123"]), "1704672000000\t1705276799999\t2024-W02\n");
+    assert_eq!(ms(&["between", "0", "1500", "--step", "1s"]), "1\n");
+    // A fraction past 12:00:00 makes that second the previous 12:00
+    assert_eq!(ms(&["prev-time", "12:00", "--from", "1704888000500"]), "1704888000000\n");
+    assert_eq!(ms(&["next-time", "12:00", "--from", "1704888000500"]), "1704974400000\n");
+    assert_eq!(
+        ms(&["epoch-bits", "2147483647000"]),
+        "i32: fits\nu32: fits\nlast second representable in signed 32-bit time\n"
+    );
+}
+
+#[test]
+fn auto_precision_reads_each_line_at_its_own_unit() {
+    let input = "1704888000\n1704888000123\n1704888001\n1704888001999\n";
+    let output = et_with_stdin(&["--auto-precision"], input);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000\n1704888000\n1704888001\n1704888001\n");
+    let output = et_with_stdin(&["--precision", "ms", "--auto-precision", "+1s"], input);
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "1704888001000\n1704888001123\n1704888002000\n1704888002999\n"
+    );
+    // Without the flag every line is read at --precision
+    let output = et_with_stdin(&[], "1704888000123\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000123\n");
+}
+
+#[test]
+fn group_by_counts_timestamps_per_bucket() {
+    let input = "1704891700\n1704888000\n\n1704889000\n1704888001\n";
+    let output = et_with_stdin(&["--group-by", "1h"], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000 3\n1704891600 1\n");
+    assert!(!et(&["--group-by", "1M"]).status.success());
+}
+
+#[test]
+fn default_precision_from_environment() {
+    let run = |args: &[&str], precision: &str| {
+        let output = et_command(args)
+            .env("ET_DEFAULT_PRECISION", precision)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    // A 13-digit value reads as milliseconds rather than seconds far in the future
+    assert_eq!(run(&["format", "1704888000123"], "ms"), "2024-01-10T12:00:00.123Z\n");
+    assert_eq!(run(&["format", "1704888000"], ""), "2024-01-10T12:00:00Z\n");
+    assert_eq!(run(&["start-of", "day", "1704912345123"], "ms"), "1704844800000\n");
+    assert_eq!(run(&["since", "1704912345123", "--relative-to", "1704912346000"], "ms"), "877\n");
+    assert_eq!(run(&["floor", "1704912345123", "--to", "1h"], "ms"), "1704909600000\n");
+    assert_eq!(run(&["week", "1704912345123"], "ms"), "1704672000000\t1705276799999\t2024-W02\n");
+    // The flag wins over the environment
+    assert_eq!(run(&["--precision", "s", "format", "1704888000"], "ms"), "2024-01-10T12:00:00Z\n");
+}
+
+#[test]
+fn default_duration_from_environment() {
+    let run = |args: &[&str], duration: &str| {
+        let output = et_command(args)
+            .env("ET_DURATION", duration)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    // Applied to now, or to an epoch given without a duration
+    assert_eq!(run(&["--now", "1704888000"], "-7d"), "1704283200\n");
+    assert_eq!(run(&["1704888000"], "+1h"), "1704891600\n");
+    // An explicit duration wins, whether alone or after an epoch
+    assert_eq!(run(&["--now", "1704888000", "+1d"], "-7d"), "1704974400\n");
+    assert_eq!(run(&["1704888000", "1h"], "-7d"), "1704891600\n");
+    assert_eq!(run(&["--now", "1704888000"], ""), "1704888000\n");
+    // And to each line of stdin
+    let mut child = et_command(&[])
+        .env("ET_DURATION", "-1d")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"86400\n172800\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n86400\n");
+}
+
+#[test]
+fn parse_round_versus_truncate() {
+    assert_eq!(stdout(&["parse", "2024-01-10T12:00:00.999Z"]), "1704888000\n");
+    assert_eq!(stdout(&["parse", "--round", "2024-01-10T12:00:00.999Z"]), "1704888001\n");
+}
+
+#[test]
+fn strict_iso_rejects_offsets_without_colons() {
+    assert_eq!(stdout(&["parse", "2024-01-10T12:00:00+0200"]), "1704880800\n");
+    let output = et(&["--strict-iso", "parse", "2024-01-10T12:00:00+0200"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("strict mode"));
+    assert_eq!(stdout(&["--strict-iso", "parse", "2024-01-10T12:00:00+02:00"]), "1704880800\n");
+    assert!(!et(&["--strict-iso", "format", "2024-01-10T12:00Z"]).status.success());
+    // RFC 3339 needs a timezone, so a zoneless timestamp fails too
+    assert!(!et(&["--strict-iso", "parse", "2024-01-10T12:00:00"]).status.success());
+    assert!(!et(&["--strict-iso", "--lenient", "parse", "2024-01-10T12:00:00Z"]).status.success());
+}
+
+#[test]
+fn parse_detects_the_input_format() {
+    assert_eq!(stdout(&["parse", "Wed, 10 Jan 2024 12:00:00 +0000"]), "1704888000\n");
+    assert_eq!(stdout(&["parse", "2024-01-10 12:00:00Z"]), "1704888000\n");
+    assert_eq!(stdout(&["parse", "2024-01-10"]), "1704844800\n");
+    assert_eq!(stdout(&["parse", "1704888000"]), "1704888000\n");
+    assert_eq!(stdout(&["--precision", "ms", "parse", "2024-01-10 12:00:00.5Z"]), "1704888000500\n");
+    assert_eq!(stdout(&["--precision", "ms", "parse", "2024-01-10T12:00:00.5Z"]), "1704888000500\n");
+    assert_eq!(stdout(&["parse", "--round", "2024-01-10 12:00:00.5Z"]), "1704888001\n");
+    let output = et(&["parse", "garbage"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("tried epoch, ISO-8601, RFC 2822"));
+}
+
+#[test]
+fn parse_input_format_restricts_detection() {
+    assert_eq!(stdout(&["parse", "--input-format", "date", "2024-01-10"]), "1704844800\n");
+    let rfc3339 = ["--precision", "ms", "parse", "--input-format", "rfc3339", "2024-01-10 12:00:00.25Z"];
+    assert_eq!(stdout(&rfc3339), "1704888000250\n");
+    let epoch = ["--precision", "ms", "parse", "--input-format", "epoch", "1704888000123"];
+    assert_eq!(stdout(&epoch), "1704888000123\n");
+    assert!(!et(&["parse", "--input-format", "iso", "2024-01-10"]).status.success());
+    assert!(!et(&["parse", "--input-format", "rfc2822", "1704888000"]).status.success());
+}
+
+// Color
+#[test]
+fn color_never_has_no_escape_codes() {
+    let out = stdout(&["--color", "never", "format", "1704888000"]);
+    assert_eq!(out, "2024-01-10T12:00:00Z\n");
+    let out = stdout(&["--color", "never", "--now", "1704888000", "since", "0", "--human"]);
+    assert!(!out.contains('\x1b'));
+}
+
+#[test]
+fn color_is_off_in_pipes_by_default() {
+    assert!(!stdout(&["format", "1704888000"]).contains('\x1b'));
+}
+
+#[test]
+fn color_always_colors_iso() {
+    let out = stdout(&["--color", "always", "format", "1704888000"]);
+    assert!(out.starts_with("\x1b[34m2024-01-10"));
+}
+
+// Duration Lists
+#[test]
+fn duration_list_sums() {
+    assert_eq!(stdout(&["0", "1h,30m"]), "5400\n");
+    assert_eq!(stdout(&["--now", "0", "1h 30m"]), "5400\n");
+    assert!(!et(&["0", "1h,1M"]).status.success());
+}
+
+// Assumed Timezone
+#[test]
+fn naive_timestamps_need_a_timezone_by_default() {
+    assert!(!et(&["parse", "2024-01-10T12:00:00"]).status.success());
+}
+
+#[test]
+fn assume_timezone_applies_to_naive_timestamps() {
+    let parse = ["--assume-timezone", "+05:30", "parse", "2024-01-10T12:00:00"];
+    assert_eq!(stdout(&parse), "1704868200\n");
+    let since = ["--assume-timezone", "-08:00", "--now", "1704888000", "since"];
+    assert_eq!(stdout(&[&since[..], &["2024-01-10T00:00:00"]].concat()), "14400\n");
+}
+
+#[test]
+fn assume_timezone_from_environment() {
+    let output = et_command(&["parse", "2024-01-10T12:00:00"])
+        .env("ET_ASSUME_TIMEZONE", "+01:00")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704884400\n");
+}
+
+#[test]
+fn local_conflicts_with_assume_timezone() {
+    for args in [
+        &["parse", "--local", "--assume-timezone", "+02:00", "2024-01-10T12:00:00"][..],
+        &["--assume-timezone", "+02:00", "parse", "--local", "2024-01-10T12:00:00"],
+    ] {
+        let output = et(args);
+        assert!(!output.status.success(), "et {args:?}");
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("--local and --assume-timezone"), "{stderr}");
+    }
+
+    let output = et_command(&["parse", "--local", "2024-01-10T12:00:00"])
+        .env("TZ", "UTC")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000\n");
+    // An explicit --local still overrides the environment
+    let output = et_command(&["parse", "--local", "2024-01-10T12:00:00"])
+        .env("TZ", "UTC")
+        .env("ET_ASSUME_TIMEZONE", "+01:00")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704888000\n");
+}
+
+// Add and Sub
+#[test]
+fn sub_matches_negative_shorthand() {
+    let sub = stdout(&["--now", "1704888000", "sub", "now", "1d"]);
+    assert_eq!(sub, stdout(&["--now", "1704888000", "now", "-1d"]));
+    assert_eq!(sub, "1704801600\n");
+}
+
+#[test]
+fn add_and_sub_default_to_now() {
+    assert_eq!(stdout(&["--now", "1704888000", "add", "1h"]), "1704891600\n");
+    assert_eq!(stdout(&["--now", "1704888000", "sub", "1M"]), "1702209600\n");
+    assert_eq!(stdout(&["add", "1704888000", "+1h"]), "1704891600\n");
+}
+
+// Explain
+#[test]
+fn explain_describes_instead_of_printing() {
+    let out = stdout(&["--explain", "--now", "1711886400", "-1M"]);
+    assert_eq!(
+        out,
+        "Subtracting 1 calendar month: 2024-03-31T12:00:00Z → 2024-02-29T12:00:00Z, \
+         clamping day 31 to 29\n"
+    );
+    assert!(!stdout(&["--explain", "1705276800", "+1M"]).contains("clamping"));
+}
+
+// Random
+#[test]
+fn random_with_seed_lands_in_range() {
+    let args = ["random", "--from", "2020-01-01T00:00:00Z", "--to", "2025-01-01T00:00:00Z"];
+    let seeded = [&args[..], &["--count", "10", "--seed", "42"]].concat();
+    let out = stdout(&seeded);
+    let epochs: Vec<i64> = out.lines().map(|l| l.parse().unwrap()).collect();
+    assert_eq!(epochs.len(), 10);
+    assert!(epochs.iter().all(|e| (1577836800..1735689600).contains(e)));
+    assert_eq!(out, stdout(&seeded));
+}
+
+#[test]
+fn random_rejects_empty_range() {
+    assert!(!et(&["random", "--from", "10", "--to", "5"]).status.success());
+}
+
+// Periods
+#[test]
+fn start_of_week_honors_week_start() {
+    assert_eq!(stdout(&["start-of", "week", "2024-01-10T12:00:00Z"]), "1704672000\n");
+    let sunday = ["start-of", "week", "2024-01-10T12:00:00Z", "--week-start", "sun"];
+    assert_eq!(stdout(&sunday), "1704585600\n");
+    let end = ["end-of", "week", "2024-01-10T12:00:00Z", "--week-start", "sun"];
+    assert_eq!(stdout(&end), "1705190399\n");
+}
+
+#[test]
+fn start_of_defaults_to_now() {
+    assert_eq!(stdout(&["--now", "1704890096", "start-of", "day"]), "1704844800\n");
+}
+
+#[test]
+fn range_prints_both_ends_of_a_period() {
+    // March 2024: 2024-03-01T00:00:00Z to 2024-03-31T23:59:59Z
+    assert_eq!(stdout(&["range", "month", "2024-03-15T00:00:00Z"]), "1709251200\t1711929599\n");
+    // February 2024 has 29 days: 2024-02-01T00:00:00Z to 2024-02-29T23:59:59Z
+    assert_eq!(
+        stdout(&["range", "month", "2024-02-10T00:00:00Z", "--json"]),
+        "{\"start\":1706745600,\"end\":1709251199}\n"
+    );
+    assert_eq!(stdout(&["range", "week", "2024-01-10T12:00:00Z", "--week-start", "sun"]), "1704585600\t1705190399\n");
+}
+
+#[test]
+fn week_prints_the_iso_week_and_its_label() {
+    assert_eq!(stdout(&["week", "2024-03-13T00:00:00Z"]), "1710115200\t1710719999\t2024-W11\n");
+    // 2024-12-31 is in the first week of 2025, which starts Monday 2024-12-30
+    assert_eq!(stdout(&["week", "2024-12-31T12:00:00Z"]), "1735516800\t1736121599\t2025-W01\n");
+    // 2021-01-01 is in the last week of 2020, which starts Monday 2020-12-28
+    assert_eq!(stdout(&["whatweek", "2021-01-01T00:00:00Z"]), "1609113600\t1609718399\t2020-W53\n");
+    assert_eq!(stdout(&["--now", "1710288000", "week"]), "1710115200\t1710719999\t2024-W11\n");
+}
+
+#[test]
+fn exclusive_end_ends_at_the_next_period() {
+    // February 2024 ends at 2024-02-29T23:59:59Z inclusive, 2024-03-01T00:00:00Z exclusive
+    assert_eq!(stdout(&["end-of", "month", "2024-02-10T00:00:00Z"]), "1709251199\n");
+    assert_eq!(stdout(&["end-of", "month", "2024-02-10T00:00:00Z", "--exclusive-end"]), "1709251200\n");
+    assert_eq!(stdout(&["range", "month", "2024-02-10T00:00:00Z", "--exclusive-end"]), "1706745600\t1709251200\n");
+}
+
+// Duration Formatting
+#[test]
+fn fmt_duration_breaks_down_seconds() {
+    assert_eq!(stdout(&["fmt-duration", "90061"]), "1d 1h 1m 1s\n");
+    assert_eq!(stdout(&["fmt-duration", "-60"]), "-1m\n");
+    assert!(!et(&["fmt-duration", "soon"]).status.success());
+}
+
+// Output Format
+#[test]
+fn format_flag_and_environment_precedence() {
+    let run = |args: &[&str], format: &str| {
+        let output = et_command(args)
+            .env("ET_FORMAT", format)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(run(&["format", "1704888000"], "iso-basic"), "20240110T120000Z\n");
+    assert_eq!(run(&["format", "1704888000"], "[year]"), "2024\n");
+    assert_eq!(run(&["format", "1704888000"], ""), "2024-01-10T12:00:00Z\n");
+    let flag = ["format", "1704888000", "--format", "rfc2822"];
+    assert_eq!(run(&flag, "iso-basic"), "Wed, 10 Jan 2024 12:00:00 +0000\n");
+}
+
+#[test]
+fn format_week_of_month() {
+    assert_eq!(stdout(&["format", "2024-01-09T12:00:00Z", "--format", "wom"]), "2\n");
+    assert_eq!(stdout(&["format", "2024-01-31T12:00:00Z", "--format", "wom"]), "5\n");
+}
+
+#[test]
+fn format_reads_the_timestamp_from_a_file() {
+    let dir = concat!(env!("CARGO_TARGET_TMPDIR"), "/format-input");
+    fs::create_dir_all(dir).unwrap();
+    let valid = format!("{dir}/valid");
+    fs::write(&valid, "1704888000\n").unwrap();
+    assert_eq!(stdout(&["format", "--input", &valid]), "2024-01-10T12:00:00Z\n");
+
+    let invalid = format!("{dir}/invalid");
+    fs::write(&invalid, "not a timestamp\n").unwrap();
+    let output = et(&["format", "--input", &invalid]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with(&format!("error: {invalid}: ")));
+
+    let missing = format!("{dir}/missing");
+    let output = et(&["format", "--input", &missing]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with(&format!("error: {missing}: ")));
+    assert!(!et(&["format", "1704888000", "--input", &valid]).status.success());
+}
+
+// Day Numbers
+#[test]
+fn format_days_since_epoch() {
+    let positive = ["format", "1704888000", "--days-since-epoch"];
+    assert_eq!(stdout(&positive), "2024-01-10T12:00:00Z\n19732\n");
+    let negative = ["format", "--days-since-epoch", "--", "-1"];
+    assert_eq!(stdout(&negative), "1969-12-31T23:59:59Z\n-1\n");
+}
+
+// Calendar Queries
+#[test]
+fn calinfo_reports_days_and_leap_years() {
+    assert_eq!(stdout(&["calinfo", "2024-02"]), "29 days, leap year\n");
+    assert_eq!(stdout(&["calinfo", "2023-02"]), "28 days, not a leap year\n");
+    assert_eq!(stdout(&["calinfo", "2023-12"]), "31 days, not a leap year\n");
+    assert!(!et(&["calinfo", "2023-13"]).status.success());
+    assert!(!et(&["calinfo", "2023"]).status.success());
+}
+
+#[test]
+fn nth_weekday_finds_occurrences() {
+    // The 3rd Monday of March 2024 is the 18th
+    assert_eq!(stdout(&["nth-weekday", "2024", "3", "monday", "3"]), "1710720000\n");
+    // The last Friday of March 2024 is the 29th
+    assert_eq!(stdout(&["nth-weekday", "2024", "3", "fri", "-1"]), "1711670400\n");
+    let output = et(&["nth-weekday", "2024", "2", "fri", "5"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("February 2024 has no 5th Friday"));
+}
+
+#[test]
+fn next_and_prev_time_step_over_the_reference() {
+    let next = ["next-time", "09:00", "--from", "2024-01-10T10:00:00Z"];
+    assert_eq!(stdout(&next), "1704963600\n");
+    let prev = ["prev-time", "09:00", "--from", "2024-01-10T10:00:00Z"];
+    assert_eq!(stdout(&prev), "1704877200\n");
+    let next = ["next-time", "09:00", "--from", "1704880800", "--offset", "+05:30"];
+    assert_eq!(stdout(&next), "1704943800\n");
+    assert!(!et(&["next-time", "25:00"]).status.success());
+}
+
+// Validation
+#[test]
+fn validate_exits_quietly_on_success() {
+    for input in ["1704888000", "2024-01-10T12:00:00Z", "-1"] {
+        let output = et(&["validate", input]);
+        assert!(output.status.success(), "{input}");
+        assert!(output.stdout.is_empty() && output.stderr.is_empty());
+    }
+    for input in ["yesterday", "2024-01-10T12:00:00", "2024-13-01T00:00:00Z"] {
+        let output = et(&["validate", input]);
+        assert!(!output.status.success(), "{input}");
+        assert!(output.stdout.is_empty());
+    }
+}
+
+#[test]
+fn validate_require_enforces_the_kind() {
+    assert!(et(&["validate", "1704888000", "--require", "epoch"]).status.success());
+    assert!(!et(&["validate", "1704888000", "--require", "iso"]).status.success());
+    assert!(et(&["validate", "2024-01-10T12:00:00Z", "--require", "iso"]).status.success());
+    assert!(!et(&["validate", "2024-01-10T12:00:00Z", "--require", "epoch"]).status.success());
+}
+
+// Annotation
+#[test]
+fn annotate_pairs_epoch_and_iso() {
+    assert_eq!(stdout(&["--annotate", "format", "1704912345"]), "1704912345 (2024-01-10T18:45:45Z)\n");
+    assert_eq!(stdout(&["parse", "2024-01-10T18:45:45Z", "--annotate"]), "1704912345 (2024-01-10T18:45:45Z)\n");
+    let offset = ["format", "1704912345", "--annotate", "--output-tz", "-05:00"];
+    assert_eq!(stdout(&offset), "1704912345 (2024-01-10T13:45:45-05:00)\n");
+    let millis = ["--precision", "ms", "--annotate", "format", "1704912345123"];
+    assert_eq!(stdout(&millis), "1704912345123 (2024-01-10T18:45:45.123Z)\n");
+    assert_eq!(stdout(&["1704912345", "+1h", "--annotate"]), "1704915945 (2024-01-10T19:45:45Z)\n");
+}
+
+// Epoch Zero Warning
+#[test]
+fn warn_epoch_zero_flags_zero_results() {
+    let output = et(&["--warn-epoch-zero", "format", "0"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1970-01-01T00:00:00Z\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("warning: result is epoch 0"));
+    let output = et(&["--warn-epoch-zero", "3600", "-1h"]);
+    assert!(String::from_utf8(output.stderr).unwrap().contains("warning: result is epoch 0"));
+}
+
+#[test]
+fn warn_epoch_zero_is_quiet_otherwise() {
+    let output = et(&["--warn-epoch-zero", "format", "1"]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+    // Off by default
+    assert!(et(&["format", "0"]).stderr.is_empty());
+}
+
+// Templates
+#[test]
+fn template_combines_input_and_result() {
+    let output = et_with_stdin(&["--template", "{input} -> {result} ({iso})", "+1h"], "1704888000\n0\n-3600\n");
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "1704888000 -> 1704891600 (2024-01-10T13:00:00Z)\n0 -> 3600 (1970-01-01T01:00:00Z)\n-3600 -> 0 (1970-01-01T00:00:00Z)\n"
+    );
+    let output = et_with_stdin(&["--template", "{{{result}}}"], "1704888000\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "{1704888000}\n");
+    let output = et_with_stdin(&["--template", "{input}: {result}", "apply"], "0 1d\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0 1d: 86400\n");
+}
+
+#[test]
+fn template_applies_to_arguments() {
+    let template = ["--template", "{input}->{result}"];
+    assert_eq!(stdout(&[&template[..], &["1704888000", "+1h"]].concat()), "1704888000->1704891600\n");
+    assert_eq!(stdout(&[&template[..], &["1704888000"]].concat()), "1704888000->1704888000\n");
+    assert_eq!(stdout(&[&template[..], &["--now", "0", "+1h"]].concat()), "now->3600\n");
+    assert_eq!(stdout(&[&template[..], &["sub", "3600", "1h"]].concat()), "3600->0\n");
+    assert_eq!(stdout(&[&template[..], &["0..=1", "1s"]].concat()), "0..=1->0\n0..=1->1\n");
+}
+
+#[test]
+fn template_rejects_bad_placeholders() {
+    for template in ["{epoch}", "{input", "a}b"] {
+        assert!(!et(&["--template", template, "+1h"]).status.success(), "{template}");
+    }
+}
+
+// Epoch Unit Suffixes
+#[test]
+fn epoch_suffix_sets_the_unit() {
+    assert_eq!(stdout(&["format", "1704912345s"]), "2024-01-10T18:45:45Z\n");
+    assert_eq!(stdout(&["format", "1704912345000ms"]), "2024-01-10T18:45:45Z\n");
+    assert_eq!(stdout(&["--precision", "ms", "format", "1704912345s"]), "2024-01-10T18:45:45.000Z\n");
+    let output = et_with_stdin(&["+1s"], "1704912345000ms\n1704912345s\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704912346\n1704912346\n");
+    // A lone suffixed epoch is an epoch, not a duration to add to now
+    assert_eq!(stdout(&["--now", "0", "1704912345s"]), "1704912345\n");
+    assert_eq!(stdout(&["--now", "0", "1704912345000ms"]), "1704912345\n");
+    assert_eq!(stdout(&["--now", "0", "90s"]), "90\n");
+}
+
+// Epoch Keywords
+#[test]
+fn epoch_keywords() {
+    assert_eq!(stdout(&["format", "epoch"]), "1970-01-01T00:00:00Z\n");
+    assert_eq!(stdout(&["parse", "unix"]), "0\n");
+    assert_eq!(stdout(&["parse", "epoch"]), "0\n");
+    assert_eq!(stdout(&["zero", "+1d"]), "86400\n");
+    assert!(!et(&["parse", "origin"]).status.success());
+}
+
+// Input and Output Offsets
+#[test]
+fn format_reads_at_input_tz_and_renders_at_output_tz() {
+    let naive = ["--input-tz", "+05:30", "format", "2024-01-10T12:00:00", "--output-tz", "-08:00"];
+    assert_eq!(stdout(&naive), "2024-01-09T22:30:00-08:00\n");
+    // A timestamp with its own timezone ignores --input-tz
+    let zoned = ["--input-tz", "+05:30", "format", "2024-01-10T12:00:00Z", "--output-tz", "-08:00"];
+    assert_eq!(stdout(&zoned), "2024-01-10T04:00:00-08:00\n");
+    let millis = ["--precision", "ms", "format", "1704888000123", "--output-tz", "+01:00"];
+    assert_eq!(stdout(&millis), "2024-01-10T13:00:00.123+01:00\n");
+}
+
+#[test]
+fn out_of_range_offsets_are_rejected() {
+    for (offset, reason) in [("+25:00", "hours must be below 24"), ("+02:61", "minutes and seconds must be below 60")] {
+        let output = et(&["format", "0", "--output-tz", offset]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(&format!("invalid UTC offset: {offset}: {reason}")), "{stderr}");
+    }
+    assert!(!et(&["--input-tz", "+24:00", "parse", "2024-01-10T12:00:00"]).status.success());
+}
+
+// Locale Independence
+#[test]
+fn names_stay_english_under_other_locales() {
+    let run = |args: &[&str]| {
+        let output = et_command(args)
+            .env("LANG", "de_DE.UTF-8")
+            .env("LC_ALL", "fr_FR.UTF-8")
+            .env("LC_TIME", "ja_JP.UTF-8")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let rfc2822 = ["format", "1704888000", "--format", "rfc2822"];
+    assert_eq!(run(&rfc2822), "Wed, 10 Jan 2024 12:00:00 +0000\n");
+    let pattern = ["format", "1704888000", "--format", "[weekday] [month repr:long]"];
+    assert_eq!(run(&pattern), "Wednesday January\n");
+    assert!(run(&["components", "1704888000"]).contains("weekday: Wednesday\n"));
+}
+
+// Shell Completions
+#[test]
+fn completions_for_each_shell() {
+    for shell in ["bash", "zsh", "fish", "elvish", "powershell"] {
+        let script = stdout(&["completions", shell]);
+        assert!(script.contains("start-of"), "{shell} completions miss subcommands");
+    }
+    assert!(!et(&["completions", "tcsh"]).status.success());
+    assert!(!stdout(&["--help"]).contains("completions"));
+}
+
+// Help Examples
+/// Split an example command line into words, keeping single-quoted text
+/// together.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = None::<String>;
+    let mut quoted = false;
+    for c in line.chars() {
+        match c {
+            '\'' => {
+                quoted = !quoted;
+                word.get_or_insert_with(String::new);
+            }
+            ' ' if !quoted => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+#[test]
+fn help_examples_print_what_they_show() {
+    let help = stdout(&["help"]);
+    let commands: Vec<&str> = help
+        .lines()
+        .skip_while(|line| *line != "Commands:")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_whitespace().next())
+        .filter(|command| *command != "help")
+        .collect();
+    assert!(commands.len() > 20, "{help}");
+
+    for command in commands {
+        let help = stdout(&[command, "--help"]);
+        let (_, examples) = help.split_once("EXAMPLES\n").unwrap_or_else(|| panic!("{command} has no examples"));
+        // Each example is a command line indented by two spaces, followed by
+        // its output indented by four; an example without output only has to
+        // succeed
+        let mut runs: Vec<(&str, String)> = Vec::new();
+        for line in examples.lines() {
+            if let Some(output) = line.strip_prefix("    ") {
+                runs.last_mut().unwrap().1 += &format!("{output}\n");
+            } else if let Some(example) = line.strip_prefix("  ") {
+                runs.push((example, String::new()));
+            }
+        }
+        assert!(!runs.is_empty(), "{command} has no examples");
+
+        for (example, expected) in runs {
+            let (input, line) = match example.split_once(" | ") {
+                Some((echo, line)) => (Some(shell_words(echo)[1..].join(" ") + "\n"), line),
+                None => (None, example),
+            };
+            let words = shell_words(line);
+            assert_eq!(words[0], "et", "{example}");
+            let args: Vec<&str> = words[1..].iter().map(String::as_str).collect();
+            let output = match &input {
+                Some(input) => et_with_stdin(&args, input),
+                None => et(&args),
+            };
+            assert!(output.status.success(), "{example}: {}", String::from_utf8_lossy(&output.stderr));
+            if !expected.is_empty() {
+                assert_eq!(String::from_utf8(output.stdout).unwrap(), expected, "{example}");
+            }
+        }
+    }
+}
+
+// Man Page
+#[test]
+fn manpage_is_roff_with_the_commands() {
+    let page = stdout(&["manpage"]);
+    assert!(page.contains(".TH et 1"));
+    for command in ["now", "parse", "format", "start\\-of"] {
+        assert!(page.contains(&format!("et\\-{command}(1)")), "man page misses {command}");
+    }
+    assert!(!page.contains("et\\-manpage"));
+}
+
+// Config File
+#[test]
+fn config_file_sets_defaults_below_env_and_flags() {
+    let home = concat!(env!("CARGO_TARGET_TMPDIR"), "/config-defaults");
+    fs::create_dir_all(format!("{home}/et")).unwrap();
+    let config = "# defaults\nformat = \"iso-basic\"\nprecision = 'ms'\ntimezone = \"+05:30\"\nweek_start = \"sun\"\n";
+    fs::write(format!("{home}/et/config.toml"), config).unwrap();
+    let run = |args: &[&str], env: &[(&str, &str)]| {
+        let output = et_command(args)
+            .env("XDG_CONFIG_HOME", home)
+            .envs(env.iter().copied())
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(run(&["format", "1704888000123"], &[]), "20240110T120000Z\n");
+    assert_eq!(run(&["parse", "2024-01-10T17:30:00"], &[]), "1704888000000\n");
+    assert_eq!(run(&["start-of", "week", "1704888000000"], &[]), "1704585600000\n");
+    // The environment overrides the file, and flags override both
+    assert_eq!(run(&["format", "1704888000123"], &[("ET_FORMAT", "iso")]), "2024-01-10T12:00:00.123Z\n");
+    let flags = ["--precision", "s", "format", "1704888000", "--format", "rfc3339"];
+    assert_eq!(run(&flags, &[("ET_FORMAT", "iso")]), "2024-01-10T12:00:00Z\n");
+    assert_eq!(run(&["start-of", "week", "1704888000000", "--week-start", "mon"], &[]), "1704672000000\n");
+}
+
+#[test]
+fn invalid_config_file_is_reported() {
+    let home = concat!(env!("CARGO_TARGET_TMPDIR"), "/config-invalid");
+    fs::create_dir_all(format!("{home}/et")).unwrap();
+    fs::write(format!("{home}/et/config.toml"), "precision = \"fortnights\"\n").unwrap();
+    let run = |args: &[&str]| {
+        et_command(args).env("XDG_CONFIG_HOME", home).stdin(Stdio::null()).output().unwrap()
+    };
+    let output = run(&["now"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("invalid config file: line 1"));
+    // Commands that never read the config still work
+    assert!(run(&["completions", "bash"]).status.success());
+}
+
+// Empty Stdin
+#[test]
+fn empty_stdin_falls_back_to_now_by_default() {
+    let output = et_with_stdin(&["--now", "1704888000", "-1d"], "");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704801600\n");
+}
+
+#[test]
+fn empty_stdin_with_no_fallback_is_an_error() {
+    let output = et_with_stdin(&["--now", "1704888000", "-1d", "--no-fallback"], "\n\n");
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("no input"));
+
+    let output = et_with_stdin(&["-1d", "--no-fallback"], "1704888000\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704801600\n");
+}
+
+// Stdin Limits
+#[test]
+fn max_lines_stops_and_reports_truncation() {
+    let output = et_with_stdin(&["--max-lines", "2", "+1s"], "1\n2\n3\n4\n");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n3\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("stopped after 2 lines"));
+}
+
+#[test]
+fn max_lines_allows_input_up_to_the_limit() {
+    let output = et_with_stdin(&["--max-lines", "2"], "1\n\n2\n\n");
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n2\n");
+}
+
+#[test]
+fn stdin_flags_follow_subcommands() {
+    let output = et_with_stdin(&["apply", "--max-lines", "1"], "0 1s\n0 2s\n");
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+
+    let output = et_with_stdin(&["apply", "--no-fallback"], "");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("no input"));
+
+    let output = et_with_stdin(&["apply", "--template", "{input} = {result}"], "0 1d\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0 1d = 86400\n");
+
+    let output = et_with_stdin(&["apply", "--from-iso"], "1970-01-01T00:00:00Z 1s\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1\n");
+}
+
+// Sequence Counts
+#[test]
+fn seq_count_only() {
+    assert_eq!(stdout(&["seq", "0..86400", "--step", "1h", "--count-only"]), "24\n");
+    let year = "2024-01-01T00:00:00Z..2025-01-01T00:00:00Z";
+    let months = ["seq", year, "--step", "+1M", "--count-only"];
+    assert_eq!(stdout(&months), "12\n");
+    assert!(!et(&["seq", "0..10", "--step", "-1s", "--count-only"]).status.success());
+}
+
+// Relative Reference
+#[test]
+fn since_and_until_measure_against_relative_to() {
+    let since = ["since", "1704067200", "--relative-to", "2024-01-02T00:00:00Z"];
+    assert_eq!(stdout(&since), "86400\n");
+    let human = ["since", "1704067200", "--relative-to", "1704153600", "--human", "--color", "never"];
+    assert_eq!(stdout(&human), "1 day ago\n");
+    let abbrev = ["since", "1704067200", "--relative-to", "1704153600", "--human", "--abbrev", "--color", "never"];
+    assert_eq!(stdout(&abbrev), "1d ago\n");
+    let abbrev = ["until", "1704153600", "--relative-to", "1704067200", "--human", "--abbrev", "--color", "never"];
+    assert_eq!(stdout(&abbrev), "in 1d\n");
+    assert!(!et(&["since", "1704067200", "--abbrev"]).status.success());
+    // 1 day 3 hours 5 minutes
+    let parts = ["since", "1704067200", "--relative-to", "1704164700", "--human", "--parts", "2", "--color", "never"];
+    assert_eq!(stdout(&parts), "1 day 3 hours ago\n");
+    assert!(!et(&["until", "1704067200", "--parts", "2"]).status.success());
+    let until = ["until", "1704153600", "--relative-to", "1704067200", "--now", "0"];
+    assert_eq!(stdout(&until), "86400\n");
+}
+
+// Counting Steps
+#[test]
+fn between_counts_whole_steps() {
+    let months = ["between", "2024-01-15T00:00:00Z", "2024-07-20T00:00:00Z", "--step", "1M"];
+    assert_eq!(stdout(&months), "6\n");
+    assert_eq!(stdout(&["between", "0", "1000000", "--step", "1d"]), "11\n");
+}
+
+// Rounding to Multiples
+#[test]
+fn floor_and_ceil_to_quarter_hours() {
+    assert_eq!(stdout(&["floor", "1704912345", "--to", "15m"]), "1704912300\n");
+    assert_eq!(stdout(&["ceil", "1704912345", "--to", "15m"]), "1704913200\n");
+    assert_eq!(stdout(&["ceil", "1704912300", "--to", "15m"]), "1704912300\n");
+}
+
+#[test]
+fn rounding_rejects_calendar_durations() {
+    for command in ["floor", "ceil"] {
+        let output = et(&[command, "1704912345", "--to", "+1M"]);
+        assert!(!output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("1M (months and years have no fixed length"), "{stderr}");
+    }
+}
+
+// Negative Durations
+#[test]
+fn negative_durations_with_and_without_separator() {
+    let now = ["--now", "1704067200"];
+    for args in [&["now", "-7d"][..], &["now", "--", "-7d"], &["-7d"], &["--", "-7d"]] {
+        let args = [&now[..], args].concat();
+        assert_eq!(stdout(&args), "1703462400\n", "et {args:?}");
+    }
+    let flags = ["--now", "1704067200", "-n", "--pad", "12", "now", "-7d"];
+    assert_eq!(stdout(&flags), "001703462400");
+    let flags = ["--now", "1704067200", "--pad", "12", "sub", "--", "-7d"];
+    assert_eq!(stdout(&flags), "001704672000\n");
+}
+
+#[test]
+fn bare_duration_after_separator_applies_to_now() {
+    for (duration, expected) in [("+1h", "1704070800"), ("-7d", "1703462400")] {
+        let bare = stdout(&["--now", "1704067200", "--times", "1", duration]);
+        let separated = stdout(&["--now", "1704067200", "--times", "1", "--", duration]);
+        assert_eq!(bare, format!("{expected}\n"));
+        assert_eq!(separated, bare);
+    }
+    let annotated = stdout(&["--now", "1704067200", "--annotate", "--", "+1h"]);
+    assert_eq!(annotated, "1704070800 (2024-01-01T01:00:00Z)\n");
+    let output = et_with_stdin(&["--negate", "--", "+1h"], "0\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "-3600\n");
+}
+
+#[test]
+fn no_short_flag_looks_like_a_negative_duration() {
+    // A short flag such as -7 would swallow negative durations like -7d
+    let help = stdout(&["--help"]);
+    for line in help.lines() {
+        let flag = line.trim_start().strip_prefix('-').and_then(|f| f.chars().next());
+        assert!(!flag.is_some_and(|c| c.is_ascii_digit()), "short flag in: {line}");
+    }
+}
+
+// Negation
+#[test]
+fn negate_flips_the_duration() {
+    assert_eq!(stdout(&["1704067200", "+1h", "--negate"]), "1704063600\n");
+    assert_eq!(stdout(&["--negate", "add", "1704067200", "1M"]), "1701388800\n");
+    assert_eq!(stdout(&["--negate", "sub", "1704067200", "1d"]), "1704153600\n");
+    let output = et_with_stdin(&["--negate", "-1d"], "1704067200\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704153600\n");
+}
+
+// Dry Run
+#[test]
+fn dry_run_prints_normalized_durations() {
+    assert_eq!(stdout(&["--dry-run", "1704912345", "1hour,1.5m"]), "1h\n90s\n");
+    assert_eq!(stdout(&["--dry-run", "-2M"]), "-2M\n");
+    let output = et(&["--dry-run", "1h,3x"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("duration 2: "));
+}
+
+// Saturation
+#[test]
+fn saturate_clamps_overflowing_results() {
+    assert!(!et(&["9223372036854775800", "+1h"]).status.success());
+    assert_eq!(stdout(&["--saturate", "9223372036854775800", "+1h"]), "9223372036854775807\n");
+    let min = ["--saturate", "sub", "--", "-9223372036854775800", "1h"];
+    assert_eq!(stdout(&min), "-9223372036854775808\n");
+    assert_eq!(stdout(&["--saturate", "1704067200", "+1h"]), "1704070800\n");
+}
+
+// Scaling
+#[test]
+fn times_scales_the_duration() {
+    assert_eq!(stdout(&["1704067200", "1w", "--times", "5"]), "1707091200\n");
+    assert_eq!(stdout(&["--times", "3", "add", "1704067200", "1M"]), "1711929600\n");
+    assert_eq!(stdout(&["1704067200", "1d", "--times", "-2"]), "1703894400\n");
+    assert!(!et(&["1704067200", "1Y", "--times", "100000"]).status.success());
+}
+
+// Year Mode
+#[test]
+fn year_mode_exact_days_counts_whole_days() {
+    assert_eq!(stdout(&["1709164800", "1Y"]), "1740700800\n");
+    assert_eq!(stdout(&["--year-mode", "exact-days", "1709164800", "1Y"]), "1740787200\n");
+    assert_eq!(stdout(&["--year-mode", "exact-days", "1719792000", "1Y"]), "1751328000\n");
+    let explain = ["--year-mode", "exact-days", "--explain", "1709164800", "1Y"];
+    assert_eq!(stdout(&explain), "Adding 366 days: 2024-02-29T00:00:00Z → 2025-03-01T00:00:00Z\n");
+    assert!(!et(&["--year-mode", "exact", "1709164800", "1Y"]).status.success());
+    // Negating first counts the days backwards from Feb 29, as -1Y does
+    let back = stdout(&["--year-mode", "exact-days", "1709164800", "-1Y"]);
+    assert_eq!(back, "1677628800\n");
+    assert_eq!(stdout(&["--year-mode", "exact-days", "--negate", "1709164800", "1Y"]), back);
+    assert_eq!(stdout(&["--year-mode", "exact-days", "--times", "-1", "1709164800", "1Y"]), back);
+}
+
+// Per-line Durations
+#[test]
+fn apply_uses_each_lines_duration() {
+    let input = "1704067200 +1h\n\n1704067200 -1d\n1704067200 1M\n1704067200 1h,30m\n";
+    let output = et_with_stdin(&["apply"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "1704070800\n1703980800\n1706745600\n1704072600\n"
+    );
+}
+
+#[test]
+fn apply_stops_at_a_bad_line_unless_skipping() {
+    let input = "1704067200 +1h\nbogus\nx +1h\n1704067200 +1d\n";
+    let output = et_with_stdin(&["apply"], input);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704070800\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 2: invalid format: expected EPOCH DURATION"));
+
+    let output = et_with_stdin(&["apply", "--skip-errors"], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704070800\n1704153600\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("line 2:") && stderr.contains("line 3: invalid epoch"));
+}
+
+#[test]
+fn skip_errors_stops_when_stdout_closes() {
+    let mut child = et_command(&["--skip-errors"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run et");
+    drop(child.stdout.take());
+    let input: String = (1..=5000).map(|i| format!("{i}\n")).collect();
+    // et may exit before reading all of its input
+    let _ = child.stdin.take().unwrap().write_all(input.as_bytes());
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stderr).unwrap().lines().count(), 1);
+}
+
+// ISO Input on Stdin
+#[test]
+fn from_iso_reads_iso_lines_as_epochs() {
+    let input = "2024-01-01T00:00:00Z\n\n2024-01-02T01:00:00+01:00\n2024-01-01T00:00:00.250Z\n";
+    let output = et_with_stdin(&["--from-iso"], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704067200\n1704153600\n1704067200\n");
+
+    let output = et_with_stdin(&["--from-iso", "--precision", "ms", "+1h"], input);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704070800000\n1704157200000\n1704070800250\n");
+
+    // Epochs are not ISO-8601
+    let output = et_with_stdin(&["--from-iso"], "1704067200\n");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn from_iso_skips_bad_lines_when_asked() {
+    let input = "2024-01-01T00:00:00Z\nbogus\n2024-01-02T00:00:00Z\n";
+    let output = et_with_stdin(&["--from-iso"], input);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704067200\n");
+    assert!(String::from_utf8(output.stderr).unwrap().starts_with("error: line 2: missing timezone"));
+
+    let output = et_with_stdin(&["--from-iso", "--skip-errors"], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704067200\n1704153600\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 2: missing timezone in timestamp: bogus"));
+
+    let output = et_with_stdin(&["--from-iso", "apply"], "2024-01-01T00:00:00Z +1h\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "1704070800\n");
+}
+
+#[test]
+fn iso_prints_stdin_results_as_iso() {
+    let input = "1704067200\n\n-1\n0\n";
+    let output = et_with_stdin(&["--iso"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2024-01-01T00:00:00Z\n1969-12-31T23:59:59Z\n1970-01-01T00:00:00Z\n"
+    );
+
+    let output = et_with_stdin(&["--iso", "--precision", "ms", "--output-tz", "+05:30", "+1h"], "1704067200123\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2024-01-01T06:30:00.123+05:30\n");
+
+    let output = et_with_stdin(&["apply", "--iso", "--output-tz", "-01:00"], "1704067200 -1d\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2023-12-30T23:00:00-01:00\n");
+
+    assert_eq!(stdout(&["--iso", "--output-tz", "+01:00", "1704067200"]), "2024-01-01T01:00:00+01:00\n");
+    assert!(!et(&["--output-tz", "+01:00"]).status.success());
+    assert!(!et(&["--to-iso"]).status.success());
+}
+
+// Transcoding
+#[test]
+fn transcode_rewrites_each_line() {
+    let input = "01/10/2024 12:00\n\n12/31/1969 23:59\n";
+    let from = "[month]/[day]/[year] [hour]:[minute]";
+    let output = et_with_stdin(&["transcode", "--from-format", from], input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2024-01-10T12:00:00Z\n1969-12-31T23:59:00Z\n");
+
+    let output = et_with_stdin(&["transcode", "--from-format", "iso", "--to-format", "[month]/[day]/[year]"], "2024-01-10T12:00:00Z\n");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "01/10/2024\n");
+}
+
+#[test]
+fn transcode_skips_bad_lines_when_asked() {
+    let input = "01/10/2024\n2024-01-11\n01/12/2024\n";
+    let args = ["transcode", "--from-format", "[month]/[day]/[year]"];
+    let output = et_with_stdin(&args, input);
+    assert!(!output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2024-01-10T00:00:00Z\n");
+
+    let output = et_with_stdin(&[&args[..], &["--skip-errors"]].concat(), input);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2024-01-10T00:00:00Z\n2024-01-12T00:00:00Z\n");
+    assert!(String::from_utf8(output.stderr).unwrap().contains("line 2: invalid date: 2024-01-11 does not match"));
+}
+
+// Offset Conversion
+#[test]
+fn tz_offset_converts_and_reports_difference() {
+    let args = ["tz-offset", "--from", "+02:00", "--to", "-05:00", "2024-07-01T12:00:00"];
+    assert_eq!(stdout(&args), "2024-07-01T05:00:00-05:00\n-7h\n");
+    let args = ["tz-offset", "--from", "Z", "--to", "+05:30", "2024-07-01T12:00:00Z"];
+    assert_eq!(stdout(&args), "2024-07-01T17:30:00+05:30\n5h 30m\n");
+}
+
+#[test]
+fn offsets_print_as_seconds() {
+    let args = ["tz-offset", "--from", "Z", "--to", "+05:30", "2024-07-01T12:00:00Z", "--seconds"];
+    assert_eq!(stdout(&args), "2024-07-01T17:30:00+05:30\n19800\n");
+    let args = ["tz-offset", "--from", "+02:00", "--to", "-05:00", "0", "--seconds"];
+    assert!(stdout(&args).ends_with("\n-25200\n"));
+    // A POSIX TZ rule needs no time zone database; IST-5:30 is UTC+05:30
+    let run = |args: &[&str]| et_command(args).env("TZ", "IST-5:30").stdin(Stdio::null()).output().unwrap();
+    let output = run(&["offset-now", "--seconds"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "19800\n");
+    let output = run(&["offset-now"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "+05:30\n");
+}
+
+// Lenient Parsing
+#[test]
+fn lenient_accepts_lowercase_separators() {
+    assert!(!et(&["parse", "2024-01-10t12:00:00z"]).status.success());
+    assert_eq!(stdout(&["--lenient", "parse", "2024-01-10t12:00:00z"]), "1704888000\n");
+    let since = ["--lenient", "--now", "1704888060", "since", "2024-01-10t12:00:00z"];
+    assert_eq!(stdout(&since), "60\n");
+}
+
+#[test]
+fn comma_decimals_need_no_lenient() {
+    let parse = ["--precision", "ms", "parse", "2024-01-10T12:00:00,123Z"];
+    assert_eq!(stdout(&parse), "1704888000123\n");
+    assert!(!et(&["--strict-iso", "parse", "2024-01-10T12:00:00,123Z"]).status.success());
+    let parse = ["--lenient", "--precision", "ms", "parse", "2024-01-10T12:00:00,123Z"];
+    assert_eq!(stdout(&parse), "1704888000123\n");
+    let parse = ["--lenient", "--precision", "ms", "parse", "2024-01-10t12:00:00,5z"];
+    assert_eq!(stdout(&parse), "1704888000500\n");
+}
+
+// 32-bit Limits
+#[test]
+fn epoch_bits_flags_the_2038_boundary() {
+    assert_eq!(
+        stdout(&["epoch-bits", "2147483647"]),
+        "i32: fits\nu32: fits\nlast second representable in signed 32-bit time\n"
+    );
+    assert_eq!(
+        stdout(&["epoch-bits", "2147483648"]),
+        "i32: overflows\nu32: fits\npast the Year 2038 limit by 1s\n"
+    );
+}
@@ -1,4 +1,20 @@
-use et::{apply_duration, format_iso, is_duration, parse_epoch, parse_iso, Duration, EtError};
+use et::{
+    apply_duration, apply_duration_dt, apply_duration_reported, apply_duration_saturating,
+    ceil_to, check_strict_iso, color_iso, color_relative, compose, count_steps, days_in_month,
+    days_since_epoch, decompose, end_of, explain_duration, fits_in_i32, fits_in_u32, floor_to,
+    format_iso, format_iso8601_duration, format_iso_at, format_iso_millis, format_offset,
+    format_with, humanize_duration, humanize_relative, humanize_relative_abbrev,
+    humanize_relative_parts, is_duration, is_epoch_keyword, is_leap_year, iso_week, month_name,
+    next_start_of, next_time_of_day, normalize_iso, nth_weekday, pad_epoch, parse_any,
+    parse_any_timestamp, parse_epoch, parse_iso, parse_iso_assuming, parse_iso_dt,
+    parse_iso_lenient, parse_iso_millis, parse_iso_nanos, parse_iso_rounded, parse_iso_strict,
+    parse_offset, parse_time_of_day, parse_timestamp, parse_timestamp_assuming, parse_weekday,
+    parse_with, prev_time_of_day, require_fixed, sequence, sequence_len, since, start_of, until,
+    week_of_month, weekday_name, write_epoch, write_iso, Config, Duration, DurationKind,
+    EpochRange, EtError, FixedUnit, InputFormat, MAX_EPOCH, MIN_EPOCH, Period, Precision,
+    RandomEpochs, Timestamp, WeekStart, YearMode,
+};
+use time::{Month, OffsetDateTime, Time, UtcOffset, Weekday};
 
 // Duration Parsing - Fixed Units
 #[test]
@@ -43,111 +59,1529 @@ fn duration_fixed_case_insensitive() {
     assert_eq!(Duration::parse("1W").unwrap(), Duration::parse("1w").unwrap());
 }
 
+#[test]
+fn duration_fixed_word_forms() {
+    assert_eq!(Duration::parse("1second").unwrap(), Duration::Seconds(1));
+    assert_eq!(Duration::parse("30seconds").unwrap(), Duration::Seconds(30));
+    assert_eq!(Duration::parse("5minutes").unwrap(), Duration::parse("5m").unwrap());
+    assert_eq!(Duration::parse("-1hour").unwrap(), Duration::Seconds(-3600));
+    assert_eq!(Duration::parse("2days").unwrap(), Duration::parse("2d").unwrap());
+    assert_eq!(Duration::parse("1week").unwrap(), Duration::Seconds(604800));
+    assert_eq!(Duration::parse("1sennight").unwrap(), Duration::Seconds(604800));
+}
+
+#[test]
+fn duration_fortnight() {
+    assert_eq!(Duration::parse("1fortnight").unwrap(), Duration::Seconds(1209600));
+    assert_eq!(Duration::parse("-2fortnights").unwrap(), Duration::Seconds(-2419200));
+}
+
+// Duration Parsing - Equivalent Forms
+#[test]
+fn duration_equivalent_fixed_forms_are_equal() {
+    let parse = |s| Duration::parse(s).unwrap();
+    assert_eq!(parse("60m"), parse("1h"));
+    assert_eq!(parse("24h"), parse("1d"));
+    assert_eq!(parse("7d"), parse("1w"));
+    assert_eq!(parse("2w"), parse("1fortnight"));
+    assert_eq!(parse("1.5h"), parse("90m"));
+    assert_eq!(parse("01:00:00"), parse("3600s"));
+    assert_eq!(Duration::parse_sum(&["1h", "30m"]).unwrap(), parse("90m"));
+    assert_eq!(Duration::parse_iso8601("PT1H").unwrap(), [parse("60m")]);
+}
+
+#[test]
+fn duration_equivalent_calendar_aliases_are_equal() {
+    let parse = |s| Duration::parse(s).unwrap();
+    assert_eq!(parse("1decade"), parse("10Y"));
+    assert_eq!(parse("1century"), parse("100years"));
+    assert_eq!(parse("1mo"), parse("1M"));
+}
+
 // Duration Parsing - Calendar Units (Months and Years)
 #[test]
-fn duration_months_uppercase_m() {
-    assert_eq!(Duration::parse("1M").unwrap(), Duration::Months(1));
-    assert_eq!(Duration::parse("+3M").unwrap(), Duration::Months(3));
-    assert_eq!(Duration::parse("-6M").unwrap(), Duration::Months(-6));
-    assert_eq!(Duration::parse("12M").unwrap(), Duration::Months(12));
+fn duration_months_uppercase_m() {
+    assert_eq!(Duration::parse("1M").unwrap(), Duration::Months(1));
+    assert_eq!(Duration::parse("+3M").unwrap(), Duration::Months(3));
+    assert_eq!(Duration::parse("-6M").unwrap(), Duration::Months(-6));
+    assert_eq!(Duration::parse("12M").unwrap(), Duration::Months(12));
+}
+
+#[test]
+fn duration_months_word_forms() {
+    assert_eq!(Duration::parse("1mo").unwrap(), Duration::Months(1));
+    assert_eq!(Duration::parse("2month").unwrap(), Duration::Months(2));
+    assert_eq!(Duration::parse("3months").unwrap(), Duration::Months(3));
+}
+
+#[test]
+fn duration_years() {
+    assert_eq!(Duration::parse("1Y").unwrap(), Duration::Years(1));
+    assert_eq!(Duration::parse("+2Y").unwrap(), Duration::Years(2));
+    assert_eq!(Duration::parse("-1Y").unwrap(), Duration::Years(-1));
+    assert_eq!(Duration::parse("1y").unwrap(), Duration::Years(1));
+    assert_eq!(Duration::parse("1yr").unwrap(), Duration::Years(1));
+    assert_eq!(Duration::parse("2year").unwrap(), Duration::Years(2));
+    assert_eq!(Duration::parse("3years").unwrap(), Duration::Years(3));
+}
+
+#[test]
+fn duration_large_calendar_values() {
+    assert_eq!(Duration::parse("100M").unwrap(), Duration::Months(100));
+    assert_eq!(Duration::parse("50Y").unwrap(), Duration::Years(50));
+}
+
+#[test]
+fn duration_decades_and_centuries() {
+    assert_eq!(Duration::parse("1decade").unwrap(), Duration::Years(10));
+    assert_eq!(Duration::parse("2decade").unwrap(), Duration::Years(20));
+    assert_eq!(Duration::parse("-3decades").unwrap(), Duration::Years(-30));
+    assert_eq!(Duration::parse("1century").unwrap(), Duration::Years(100));
+    assert_eq!(Duration::parse("1c").unwrap(), Duration::Years(100));
+    assert_eq!(Duration::parse("+2centuries").unwrap(), Duration::Years(200));
+}
+
+#[test]
+fn duration_century_overflow() {
+    assert!(matches!(Duration::parse("100000000c").unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn duration_signed_calendar_word_forms() {
+    assert_eq!(Duration::parse("-2months").unwrap(), Duration::Months(-2));
+    assert_eq!(Duration::parse("+3years").unwrap(), Duration::Years(3));
+    assert_eq!(Duration::parse("-1mo").unwrap(), Duration::Months(-1));
+    assert_eq!(Duration::parse("+1month").unwrap(), Duration::Months(1));
+    assert_eq!(Duration::parse("-1decade").unwrap(), Duration::Years(-10));
+}
+
+// Duration Parsing - Fractions
+#[test]
+fn duration_fractional_fixed_units() {
+    assert_eq!(Duration::parse("1.5h").unwrap(), Duration::Seconds(5400));
+    assert_eq!(Duration::parse("0.5d").unwrap(), Duration::Seconds(43200));
+    assert_eq!(Duration::parse("2.5d").unwrap(), Duration::Seconds(216000));
+    assert_eq!(Duration::parse("-1.25m").unwrap(), Duration::Seconds(-75));
+    assert_eq!(Duration::parse("+0.5weeks").unwrap(), Duration::Seconds(302400));
+}
+
+#[test]
+fn duration_fraction_rounds_to_nearest_second() {
+    assert_eq!(Duration::parse("0.4s").unwrap(), Duration::Seconds(0));
+    assert_eq!(Duration::parse("0.5s").unwrap(), Duration::Seconds(1));
+    assert_eq!(Duration::parse("-0.5s").unwrap(), Duration::Seconds(-1));
+    assert_eq!(Duration::parse("1.0001m").unwrap(), Duration::Seconds(60));
+    assert_eq!(Duration::parse("0.0000000000000000000001h").unwrap(), Duration::Seconds(0));
+}
+
+#[test]
+fn duration_unitless_decimal_is_seconds() {
+    assert_eq!(Duration::parse("-3600.0").unwrap(), Duration::Seconds(-3600));
+    assert_eq!(Duration::parse("1.9").unwrap(), Duration::Seconds(2));
+    assert_eq!(Duration::parse("1.4").unwrap(), Duration::Seconds(1));
+    assert_eq!(Duration::parse("0.0").unwrap(), Duration::Seconds(0));
+    assert_eq!(Duration::parse("-0.0").unwrap(), Duration::Seconds(0));
+    // A bare integer keeps its written unit; a decimal is plain seconds
+    assert!(matches!(Duration::parse("3600").unwrap(), Duration::Fixed { value: 3600, unit: FixedUnit::Seconds }));
+    assert!(matches!(Duration::parse("3600.0").unwrap(), Duration::Seconds(3600)));
+}
+
+#[test]
+fn duration_fraction_rejected_for_calendar_units() {
+    for s in ["1.5M", "0.5Y", "2.5months", "1.5decades", "0.5c"] {
+        assert!(matches!(Duration::parse(s), Err(EtError::InvalidDuration(_))), "{s}");
+    }
+}
+
+#[test]
+fn duration_fraction_invalid_forms() {
+    for s in ["1.h", ".5h", "1.5.5h", "1..5h"] {
+        assert!(Duration::parse(s).is_err(), "{s}");
+    }
+}
+
+// Duration Parsing - Clock Notation
+#[test]
+fn duration_clock_notation() {
+    assert_eq!(Duration::parse("01:30:00").unwrap(), Duration::Seconds(5400));
+    assert_eq!(Duration::parse("90:00").unwrap(), Duration::Seconds(5400));
+    assert_eq!(Duration::parse("0:00:05").unwrap(), Duration::Seconds(5));
+    assert_eq!(Duration::parse("36:00:00").unwrap(), Duration::Seconds(129600));
+}
+
+#[test]
+fn duration_clock_notation_signed() {
+    assert_eq!(Duration::parse("-01:30:00").unwrap(), Duration::Seconds(-5400));
+    assert_eq!(Duration::parse("+00:01").unwrap(), Duration::Seconds(1));
+}
+
+#[test]
+fn duration_clock_notation_rejects_over_60_components() {
+    assert!(matches!(Duration::parse("00:90:00"), Err(EtError::InvalidDuration(_))));
+    assert!(matches!(Duration::parse("01:00:60"), Err(EtError::InvalidDuration(_))));
+}
+
+#[test]
+fn duration_clock_notation_invalid() {
+    for s in ["1:2:3:4", "01:", ":30", "1::2", "01:3a", "-:30"] {
+        assert!(matches!(Duration::parse(s), Err(EtError::InvalidDuration(_))), "{s}");
+    }
+    assert!(matches!(Duration::parse("99999999999999999:00:00"), Err(EtError::Overflow)));
+}
+
+// Duration Parsing - Overflow Boundaries
+#[test]
+fn duration_weeks_at_i64_boundary() {
+    // i64::MAX / 604800 = 15250284452471 (remainder 2315007)
+    assert_eq!(
+        Duration::parse("15250284452471w").unwrap(),
+        Duration::Seconds(15250284452471 * 604800)
+    );
+    assert_eq!(
+        Duration::parse("-15250284452471w").unwrap(),
+        Duration::Seconds(-15250284452471 * 604800)
+    );
+    assert!(matches!(Duration::parse("15250284452472w").unwrap_err(), EtError::Overflow));
+    assert!(matches!(Duration::parse("-15250284452472w").unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn duration_days_at_i64_boundary() {
+    // i64::MAX / 86400 = 106751991167300
+    assert!(Duration::parse("106751991167300d").is_ok());
+    assert!(matches!(Duration::parse("106751991167301d").unwrap_err(), EtError::Overflow));
+    assert!(matches!(Duration::parse("7625597484988fortnight").unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn duration_seconds_at_i64_boundary() {
+    assert_eq!(Duration::parse("9223372036854775807s").unwrap(), Duration::Seconds(i64::MAX));
+    assert_eq!(Duration::parse("-9223372036854775808s").unwrap(), Duration::Seconds(i64::MIN));
+    assert!(matches!(Duration::parse("9223372036854775808s").unwrap_err(), EtError::Overflow));
+    assert!(matches!(Duration::parse("-9223372036854775809").unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn duration_huge_digit_strings_overflow() {
+    let huge = format!("{}h", "9".repeat(50));
+    assert!(matches!(Duration::parse(&huge).unwrap_err(), EtError::Overflow));
+    let huge = format!("-{}M", "9".repeat(50));
+    assert!(matches!(Duration::parse(&huge).unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn duration_calendar_at_i32_boundary() {
+    assert_eq!(Duration::parse("2147483647M").unwrap(), Duration::Months(i32::MAX));
+    assert_eq!(Duration::parse("-2147483648Y").unwrap(), Duration::Years(i32::MIN));
+    assert!(matches!(Duration::parse("2147483648M").unwrap_err(), EtError::Overflow));
+    assert!(matches!(Duration::parse("-2147483649Y").unwrap_err(), EtError::Overflow));
+}
+
+// Duration Parsing - Invalid Input
+#[test]
+fn duration_invalid_formats() {
+    assert!(Duration::parse("").is_err());
+    assert!(Duration::parse("abc").is_err());
+    assert!(Duration::parse("++5s").is_err());
+    assert!(Duration::parse("--5s").is_err());
+    assert!(Duration::parse("+").is_err());
+    assert!(Duration::parse("-").is_err());
+}
+
+#[test]
+fn duration_doubled_signs_with_word_forms() {
+    for s in ["--2months", "++3years", "+-1mo", "-+1year", "-months", "+years"] {
+        assert!(matches!(Duration::parse(s), Err(EtError::InvalidDuration(_))), "{s}");
+    }
+}
+
+#[test]
+fn duration_unknown_unit() {
+    assert!(matches!(Duration::parse("5x").unwrap_err(), EtError::UnsupportedUnit(_)));
+    assert!(matches!(Duration::parse("10foo").unwrap_err(), EtError::UnsupportedUnit(_)));
+}
+
+// Duration Parsing - ISO 8601
+#[test]
+fn iso8601_time_parts() {
+    assert_eq!(Duration::parse_iso8601("PT1H30M").unwrap(), vec![Duration::Seconds(5400)]);
+    assert_eq!(Duration::parse_iso8601("PT45S").unwrap(), vec![Duration::Seconds(45)]);
+    assert_eq!(Duration::parse_iso8601("P2W").unwrap(), vec![Duration::Seconds(1209600)]);
+}
+
+#[test]
+fn iso8601_month_versus_minute() {
+    assert_eq!(Duration::parse_iso8601("P1Y").unwrap(), vec![Duration::Years(1)]);
+    assert_eq!(Duration::parse_iso8601("P1M").unwrap(), vec![Duration::Months(1)]);
+    assert_eq!(Duration::parse_iso8601("PT1M").unwrap(), vec![Duration::Seconds(60)]);
+    assert_eq!(
+        Duration::parse_iso8601("P1MT1M").unwrap(),
+        vec![Duration::Months(1), Duration::Seconds(60)]
+    );
+}
+
+#[test]
+fn iso8601_mixed_and_negative() {
+    assert_eq!(
+        Duration::parse_iso8601("P1Y2M3DT4H5M6S").unwrap(),
+        vec![Duration::Years(1), Duration::Months(2), Duration::Seconds(3 * 86400 + 4 * 3600 + 5 * 60 + 6)]
+    );
+    assert_eq!(
+        Duration::parse_iso8601("-P1Y1D").unwrap(),
+        vec![Duration::Years(-1), Duration::Seconds(-86400)]
+    );
+    // Applied in order: 2024-01-31 + 1 month = 2024-02-29, + 1 day = 2024-03-01
+    let epoch = Duration::parse_iso8601("P1M1D")
+        .unwrap()
+        .into_iter()
+        .try_fold(1706659200, apply_duration)
+        .unwrap();
+    assert_eq!(format_iso(epoch).unwrap(), "2024-03-01T00:00:00Z");
+}
+
+#[test]
+fn iso8601_invalid() {
+    for s in ["", "P", "PT", "P1YT", "1H", "PT1", "P1H", "PT1D", "P1M1Y", "P1D1D", "P1.5D", "P-1D", "p1d"] {
+        assert!(matches!(Duration::parse_iso8601(s), Err(EtError::InvalidDuration(_))), "{s}");
+    }
+    assert!(matches!(Duration::parse_iso8601("P3000000000Y"), Err(EtError::Overflow)));
+    assert!(matches!(Duration::parse_iso8601("P99999999999999999W"), Err(EtError::Overflow)));
+}
+
+#[test]
+fn iso8601_formatting() {
+    assert_eq!(format_iso8601_duration(5400), "PT1H30M");
+    assert_eq!(format_iso8601_duration(90061), "P1DT1H1M1S");
+    assert_eq!(format_iso8601_duration(45), "PT45S");
+    assert_eq!(format_iso8601_duration(172800), "P2D");
+    assert_eq!(format_iso8601_duration(0), "PT0S");
+    assert_eq!(format_iso8601_duration(-3600), "-PT1H");
+    assert_eq!(format_iso8601_duration(i64::MIN), "-P106751991167300DT15H30M8S");
+    assert_eq!(Duration::Months(-2).to_iso8601(), "-P2M");
+    assert_eq!(Duration::Years(1).to_iso8601(), "P1Y");
+    assert_eq!(Duration::Seconds(60).to_iso8601(), "PT1M");
+}
+
+#[test]
+fn iso8601_roundtrip() {
+    for seconds in [0, 1, 59, 3600, 5400, 90061, -90061, 1209600] {
+        let text = format_iso8601_duration(seconds);
+        let parsed = Duration::parse_iso8601(&text).unwrap();
+        assert_eq!(parsed, vec![Duration::Seconds(seconds)], "{text}");
+    }
+}
+
+// Duration Sums
+#[test]
+fn parse_sum_fixed() {
+    assert_eq!(Duration::parse_sum(&["1h", "30m"]).unwrap(), Duration::Seconds(5400));
+    assert_eq!(Duration::parse_sum(&["-1h", "30m"]).unwrap(), Duration::Seconds(-1800));
+    assert_eq!(Duration::parse_sum(&["1d"]).unwrap(), Duration::Seconds(86400));
+}
+
+#[test]
+fn parse_sum_calendar() {
+    assert_eq!(Duration::parse_sum(&["1M", "2M"]).unwrap(), Duration::Months(3));
+    assert_eq!(Duration::parse_sum(&["1Y", "1decade"]).unwrap(), Duration::Years(11));
+}
+
+#[test]
+fn parse_sum_rejects_mixed_kinds() {
+    assert!(matches!(Duration::parse_sum(&["1h", "1M"]), Err(EtError::InvalidDuration(_))));
+    assert!(matches!(Duration::parse_sum(&["1M", "1Y"]), Err(EtError::InvalidDuration(_))));
+}
+
+#[test]
+fn parse_sum_invalid() {
+    assert!(matches!(Duration::parse_sum(&[]), Err(EtError::InvalidDuration(_))));
+    assert!(Duration::parse_sum(&["1h", "xyz"]).is_err());
+    let max = format!("{}s", i64::MAX);
+    assert!(matches!(Duration::parse_sum(&[&max, "1s"]), Err(EtError::Overflow)));
+}
+
+// Duration Kind
+#[test]
+fn duration_kind() {
+    assert_eq!(Duration::Seconds(60).kind(), DurationKind::Seconds);
+    assert_eq!(Duration::Months(-1).kind(), DurationKind::Months);
+    assert_eq!(Duration::Years(10).kind(), DurationKind::Years);
+}
+
+#[test]
+fn duration_is_fixed_or_calendar() {
+    assert!(Duration::Seconds(0).is_fixed());
+    assert!(!Duration::Seconds(0).is_calendar());
+    assert!(Duration::Months(1).is_calendar());
+    assert!(!Duration::Months(1).is_fixed());
+    assert!(Duration::Years(-1).is_calendar());
+    assert!(!Duration::Years(-1).is_fixed());
+}
+
+// Duration Ordering
+#[test]
+fn duration_seconds_approx() {
+    assert_eq!(Duration::Seconds(-90).as_seconds_approx(), -90);
+    assert_eq!(Duration::Months(1).as_seconds_approx(), 2629746);
+    assert_eq!(Duration::Years(1).as_seconds_approx(), 31556952);
+    assert_eq!(Duration::Years(1).as_seconds_approx(), Duration::Months(12).as_seconds_approx());
+}
+
+#[test]
+fn duration_sort_mixed_kinds() {
+    let mut durations = vec![
+        Duration::Years(1),
+        Duration::Seconds(86400 * 31),
+        Duration::Months(-1),
+        Duration::Seconds(0),
+        Duration::Months(1),
+        Duration::Seconds(86400 * 30),
+        Duration::Months(11),
+    ];
+    durations.sort();
+    assert_eq!(
+        durations,
+        vec![
+            Duration::Months(-1),
+            Duration::Seconds(0),
+            Duration::Seconds(86400 * 30),
+            Duration::Months(1),
+            Duration::Seconds(86400 * 31),
+            Duration::Months(11),
+            Duration::Years(1),
+        ]
+    );
+}
+
+#[test]
+fn duration_order_exact_within_kind() {
+    assert!(Duration::Seconds(1) < Duration::Seconds(2));
+    assert!(Duration::Months(-3) < Duration::Months(-2));
+    assert!(Duration::Years(i32::MAX) > Duration::Years(i32::MAX - 1));
+}
+
+#[test]
+fn duration_order_ties_across_kinds() {
+    // Same approximate length: ordered by kind, never equal
+    assert!(Duration::Months(12) < Duration::Years(1));
+    assert!(Duration::Seconds(2629746) < Duration::Months(1));
+    assert_ne!(Duration::Seconds(2629746).cmp(&Duration::Months(1)), std::cmp::Ordering::Equal);
+}
+
+// Duration Display
+#[test]
+fn duration_display_is_normalized() {
+    assert_eq!(Duration::parse_sum(&["1h", "30m"]).unwrap().to_string(), "5400s");
+    assert_eq!(Duration::parse("1.5h").unwrap().to_string(), "5400s");
+    assert_eq!(Duration::parse("-2months").unwrap().to_string(), "-2M");
+    assert_eq!(Duration::parse("1decade").unwrap().to_string(), "10Y");
+    assert_eq!(Duration::Seconds(0).to_string(), "0s");
+    for d in [Duration::Seconds(-90), Duration::Months(3), Duration::Years(-1)] {
+        assert_eq!(Duration::parse(&d.to_string()).unwrap(), d);
+    }
+}
+
+#[test]
+fn duration_display_keeps_the_fixed_unit() {
+    assert_eq!(Duration::parse("7d").unwrap().to_string(), "7d");
+    assert_eq!(Duration::parse("1w").unwrap().to_string(), "1w");
+    assert_eq!(Duration::parse("+90m").unwrap().to_string(), "90m");
+    assert_eq!(Duration::parse("-3hours").unwrap().to_string(), "-3h");
+    assert_eq!(Duration::parse("30").unwrap().to_string(), "30s");
+    assert_eq!(Duration::parse("1fortnight").unwrap().to_string(), "2w");
+    // The unit is only for display: lengths still compare and apply the same
+    assert_eq!(Duration::parse("7d").unwrap(), Duration::parse("1w").unwrap());
+    assert_eq!(Duration::parse("7d").unwrap(), Duration::Seconds(604800));
+    assert_eq!(Duration::parse("7d").unwrap().as_seconds(), Some(604800));
+    let fixed = Duration::Fixed { value: 7, unit: FixedUnit::Days };
+    assert_eq!(fixed.negate().unwrap().to_string(), "-7d");
+    assert_eq!(fixed.scale(2).unwrap().to_string(), "14d");
+    assert_eq!(apply_duration(0, fixed).unwrap(), 604800);
+    assert!(fixed.is_fixed());
+    // A length past the i64 range of seconds overflows rather than clamping
+    let huge = Duration::Fixed { value: i64::MAX, unit: FixedUnit::Weeks };
+    assert!(matches!(apply_duration(0, huge), Err(EtError::Overflow)));
+    assert_eq!(huge.as_seconds(), None);
+    assert_eq!(huge.to_iso8601(), format!("P{}W", i64::MAX));
+    // Equality and ordering agree past the i64 range too
+    let days = Duration::Fixed { value: i64::MAX, unit: FixedUnit::Days };
+    assert!(Duration::Seconds(i64::MAX) < days && days < huge);
+    assert_ne!(Duration::Seconds(i64::MAX), days);
+    let weeks = Duration::Fixed { value: i64::MAX / 7, unit: FixedUnit::Weeks };
+    let same = Duration::Fixed { value: i64::MAX / 7 * 7, unit: FixedUnit::Days };
+    assert_eq!(weeks, same);
+    assert_eq!(weeks.cmp(&same), std::cmp::Ordering::Equal);
+}
+
+// Duration Negation
+#[test]
+fn duration_negate() {
+    assert_eq!(Duration::Seconds(60).negate().unwrap(), Duration::Seconds(-60));
+    assert_eq!(Duration::Months(-2).negate().unwrap(), Duration::Months(2));
+    assert_eq!(Duration::Years(1).negate().unwrap(), Duration::Years(-1));
+    assert!(matches!(Duration::Seconds(i64::MIN).negate(), Err(EtError::Overflow)));
+    assert!(matches!(Duration::Months(i32::MIN).negate(), Err(EtError::Overflow)));
+    assert!(matches!(Duration::Years(i32::MIN).negate(), Err(EtError::Overflow)));
+    assert_eq!(Duration::Months(i32::MAX).negate().unwrap(), Duration::Months(-i32::MAX));
+}
+
+#[test]
+fn duration_scale() {
+    assert_eq!(Duration::Seconds(3600).scale(5).unwrap(), Duration::Seconds(18000));
+    assert_eq!(Duration::Months(2).scale(-3).unwrap(), Duration::Months(-6));
+    assert_eq!(Duration::Years(1).scale(0).unwrap(), Duration::Years(0));
+    assert_eq!(Duration::Seconds(i64::MAX / 2).scale(2).unwrap(), Duration::Seconds(i64::MAX - 1));
+    assert!(matches!(Duration::Seconds(i64::MAX).scale(2), Err(EtError::Overflow)));
+    assert!(matches!(Duration::Months(i32::MAX).scale(2), Err(EtError::Overflow)));
+    assert!(matches!(Duration::Years(i32::MIN).scale(-1), Err(EtError::Overflow)));
+}
+
+// Epoch Parsing
+#[test]
+fn parse_epoch_valid() {
+    assert_eq!(parse_epoch("1704912345").unwrap(), 1704912345);
+    assert_eq!(parse_epoch("0").unwrap(), 0);
+    assert_eq!(parse_epoch("-1000").unwrap(), -1000);
+    assert_eq!(parse_epoch("  1704912345  ").unwrap(), 1704912345);
+}
+
+#[test]
+fn parse_epoch_invalid() {
+    assert!(parse_epoch("abc").is_err());
+    assert!(parse_epoch("12.34").is_err());
+    assert!(parse_epoch("").is_err());
+}
+
+// ISO Parsing
+#[test]
+fn parse_iso_utc() {
+    assert_eq!(parse_iso("2024-01-10T12:00:00Z").unwrap(), 1704888000);
+}
+
+#[test]
+fn parse_iso_with_offset() {
+    let z = parse_iso("2024-01-10T12:00:00Z").unwrap();
+    let zero = parse_iso("2024-01-10T12:00:00+00:00").unwrap();
+    assert_eq!(z, zero);
+
+    // +02:00 means 2 hours ahead, so UTC is 2 hours earlier
+    let plus2 = parse_iso("2024-01-10T12:00:00+02:00").unwrap();
+    let expected = parse_iso("2024-01-10T10:00:00Z").unwrap();
+    assert_eq!(plus2, expected);
+}
+
+#[test]
+fn parse_iso_missing_timezone() {
+    assert!(matches!(
+        parse_iso("2024-01-10T12:00:00").unwrap_err(),
+        EtError::MissingTimezone(_)
+    ));
+}
+
+#[test]
+fn parse_iso_assuming_offset_for_naive_timestamp() {
+    let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+    // 12:00 at +02:00 is 10:00 UTC
+    assert_eq!(
+        parse_iso_assuming("2024-01-10T12:00:00", offset).unwrap(),
+        parse_iso("2024-01-10T10:00:00Z").unwrap()
+    );
+
+    let offset = UtcOffset::from_hms(-5, -30, 0).unwrap();
+    assert_eq!(
+        parse_iso_assuming("2024-01-10T12:00:00", offset).unwrap(),
+        parse_iso("2024-01-10T17:30:00Z").unwrap()
+    );
+}
+
+#[test]
+fn parse_iso_assuming_keeps_explicit_timezone() {
+    let offset = UtcOffset::from_hms(9, 0, 0).unwrap();
+    assert_eq!(parse_iso_assuming("2024-01-10T12:00:00Z", offset).unwrap(), 1704888000);
+    assert_eq!(
+        parse_iso_assuming("2024-01-10T12:00:00+02:00", offset).unwrap(),
+        parse_iso("2024-01-10T12:00:00+02:00").unwrap()
+    );
+    assert!(matches!(
+        parse_iso_assuming("not a date", offset).unwrap_err(),
+        EtError::InvalidIso(_)
+    ));
+}
+
+#[test]
+fn parse_iso_truncates_or_rounds_fraction() {
+    assert_eq!(parse_iso("2024-01-10T12:00:00.999Z").unwrap(), 1704888000);
+    assert_eq!(parse_iso_rounded("2024-01-10T12:00:00.999Z").unwrap(), 1704888001);
+    assert_eq!(parse_iso_rounded("2024-01-10T12:00:00.499Z").unwrap(), 1704888000);
+    assert_eq!(parse_iso_rounded("2024-01-10T12:00:00.5Z").unwrap(), 1704888001);
+    assert_eq!(parse_iso_rounded("1969-12-31T23:59:59.4Z").unwrap(), -1);
+}
+
+#[test]
+fn timestamp_round_to() {
+    let ns = Timestamp::from_nanos(1704888000999500000);
+    assert_eq!(ns.round_to(Precision::Millis).unwrap().value, 1704888001000);
+    assert_eq!(ns.to_precision(Precision::Millis).unwrap().value, 1704888000999);
+    assert_eq!(Timestamp::new(-1500, Precision::Millis).round_to(Precision::Seconds).unwrap().value, -1);
+    assert_eq!(Timestamp::from_seconds(1).round_to(Precision::Millis).unwrap().value, 1000);
+}
+
+#[test]
+fn lowercase_separators_strict_and_lenient() {
+    assert!(parse_iso("2024-01-10t12:00:00z").is_err());
+    assert!(parse_iso("2024-01-10t12:00:00Z").is_err());
+    assert_eq!(parse_iso_lenient("2024-01-10t12:00:00z").unwrap(), 1704888000);
+    assert_eq!(parse_iso_lenient("2024-01-10t12:00:00.5z").unwrap(), 1704888000);
+    assert_eq!(parse_iso_lenient("2024-01-10T12:00:00Z").unwrap(), 1704888000);
+}
+
+#[test]
+fn normalize_iso_leaves_offsets_alone() {
+    assert_eq!(normalize_iso("2024-01-10t12:00:00z"), "2024-01-10T12:00:00Z");
+    assert_eq!(normalize_iso("2024-01-10t12:00:00+05:30"), "2024-01-10T12:00:00+05:30");
+    assert_eq!(normalize_iso(" 2024-01-10t12:00:00-08:00 "), "2024-01-10T12:00:00-08:00");
+    assert_eq!(normalize_iso("not a timestamp"), "not a timestamp");
+}
+
+#[test]
+fn comma_decimals_and_short_fractions() {
+    // Comma decimals parse as they are, so normalizing leaves them alone
+    assert_eq!(parse_iso_millis("2024-01-10T12:00:00,123Z").unwrap(), 1704888000123);
+    assert_eq!(normalize_iso("2024-01-10t12:00:00,123z"), "2024-01-10T12:00:00,123Z");
+    assert_eq!(parse_iso_lenient("2024-01-10T12:00:00,5Z").unwrap(), 1704888000);
+    assert_eq!(parse_iso_millis("2024-01-10T12:00:00.12Z").unwrap(), 1704888000120);
+    assert!(matches!(parse_iso_strict("2024-01-10T12:00:00,5Z"), Err(EtError::InvalidIso(_))));
+}
+
+#[test]
+fn parse_offset_forms() {
+    assert_eq!(parse_offset("+05:30").unwrap(), UtcOffset::from_hms(5, 30, 0).unwrap());
+    assert_eq!(parse_offset("-0800").unwrap(), UtcOffset::from_hms(-8, 0, 0).unwrap());
+    assert_eq!(parse_offset("+02").unwrap(), UtcOffset::from_hms(2, 0, 0).unwrap());
+    assert_eq!(parse_offset("Z").unwrap(), UtcOffset::UTC);
+    assert_eq!(parse_offset("utc").unwrap(), UtcOffset::UTC);
+    assert_eq!(parse_offset("+05:30:15").unwrap(), UtcOffset::from_hms(5, 30, 15).unwrap());
+    assert_eq!(parse_offset("-053015").unwrap(), UtcOffset::from_hms(-5, -30, -15).unwrap());
+    assert_eq!(parse_offset("-23:59").unwrap(), UtcOffset::from_hms(-23, -59, 0).unwrap());
+}
+
+#[test]
+fn parse_offset_out_of_range_says_why() {
+    let message = |s: &str| parse_offset(s).unwrap_err().to_string();
+    assert_eq!(message("+25:00"), "invalid UTC offset: +25:00: hours must be below 24");
+    assert_eq!(message("+02:61"), "invalid UTC offset: +02:61: minutes and seconds must be below 60");
+    assert_eq!(message("+02:30:60"), "invalid UTC offset: +02:30:60: minutes and seconds must be below 60");
+}
+
+#[test]
+fn parse_offset_invalid() {
+    for s in ["", "05:30", "+5:30", "+24:00", "+05:60", "+05:3", "+0530:", "EST", "+05:30:1", "+05:30:15:00", "+0530150"] {
+        assert!(matches!(parse_offset(s), Err(EtError::InvalidOffset(_))), "{s}");
+    }
+}
+
+#[test]
+fn format_iso_at_offset() {
+    let offset = parse_offset("-05:00").unwrap();
+    assert_eq!(format_iso_at(1719835200, offset).unwrap(), "2024-07-01T07:00:00-05:00");
+    let offset = parse_offset("+05:30").unwrap();
+    assert_eq!(format_iso_at(0, offset).unwrap(), "1970-01-01T05:30:00+05:30");
+    assert_eq!(format_iso_at(0, UtcOffset::UTC).unwrap(), "1970-01-01T00:00:00+00:00");
+}
+
+#[test]
+fn assumed_offset_flows_into_timestamps() {
+    let offset = parse_offset("+05:30").unwrap();
+    assert_eq!(parse_timestamp_assuming("2024-01-10T12:00:00", offset).unwrap(), 1704868200);
+    assert_eq!(parse_timestamp_assuming("1704888000", offset).unwrap(), 1704888000);
+
+    let ts = Timestamp::parse_iso_assuming("2024-01-10T12:00:00.250", offset, Precision::Millis);
+    assert_eq!(ts.unwrap().value, 1704868200250);
+}
+
+#[test]
+fn parse_iso_fractional_seconds() {
+    assert_eq!(parse_iso_nanos("2024-01-10T12:00:00.123Z").unwrap(), 1704888000123000000);
+    assert_eq!(parse_iso_nanos("2024-01-10T12:00:00.000000001Z").unwrap(), 1704888000000000001);
+    assert_eq!(parse_iso_millis("2024-01-10T12:00:00.123Z").unwrap(), 1704888000123);
+    assert_eq!(parse_iso_millis("2024-01-10T12:00:00.1239Z").unwrap(), 1704888000123);
+    // Whole-second parsing truncates the fraction
+    assert_eq!(parse_iso("2024-01-10T12:00:00.999Z").unwrap(), 1704888000);
+}
+
+#[test]
+fn parse_iso_fractional_before_epoch() {
+    // Truncation goes towards the earlier instant, not towards zero
+    assert_eq!(parse_iso_nanos("1969-12-31T23:59:59.5Z").unwrap(), -500_000_000);
+    assert_eq!(parse_iso_millis("1969-12-31T23:59:59.9995Z").unwrap(), -1);
+    assert_eq!(parse_iso("1969-12-31T23:59:59.5Z").unwrap(), -1);
+}
+
+#[test]
+fn parse_iso_invalid() {
+    assert!(parse_iso("not-a-date").is_err());
+    assert!(parse_iso("2024-13-01T00:00:00Z").is_err()); // invalid month
+    assert!(parse_iso("2024-01-32T00:00:00Z").is_err()); // invalid day
+}
+
+#[test]
+fn strict_iso_shape() {
+    // parse_iso is lenient about the offset and the time
+    assert_eq!(parse_iso("2024-01-10T12:00:00+0200").unwrap(), 1704880800);
+    assert_eq!(parse_iso("2024-01-10T12:00+02:00").unwrap(), 1704880800);
+    assert!(matches!(parse_iso_strict("2024-01-10T12:00:00+0200"), Err(EtError::InvalidIso(_))));
+    assert!(matches!(parse_iso_strict("2024-01-10T12:00+02:00"), Err(EtError::InvalidIso(_))));
+    assert_eq!(parse_iso_strict("2024-01-10T12:00:00+02:00").unwrap(), 1704880800);
+    assert_eq!(parse_iso_strict("2024-01-10T12:00:00.123Z").unwrap(), 1704888000);
+    for ok in ["2024-01-10T12:00:00Z", "2024-01-10T12:00:00-05:30", "epoch"] {
+        assert!(check_strict_iso(ok).is_ok(), "{ok}");
+    }
+    for bad in ["2024-01-10T12:00:00", "20240110T120000Z", "2024-01-10 12:00:00Z", "2024-01-10T12:00:00.Z", "2024-01-10T12:00:00+02", "2024-01-10t12:00:00z", "2024-01-10"] {
+        assert!(matches!(check_strict_iso(bad), Err(EtError::InvalidIso(_))), "{bad}");
+    }
+}
+
+// Format Detection
+#[test]
+fn parse_any_detects_each_format() {
+    assert_eq!(parse_any("1704888000").unwrap(), 1704888000);
+    assert_eq!(parse_any("2024-01-10T12:00:00Z").unwrap(), 1704888000);
+    assert_eq!(parse_any("Wed, 10 Jan 2024 12:00:00 +0000").unwrap(), 1704888000);
+    assert_eq!(parse_any("2024-01-10 13:00:00+01:00").unwrap(), 1704888000);
+    assert_eq!(parse_any("2024-01-10").unwrap(), 1704844800);
+    assert_eq!(parse_any("epoch").unwrap(), 0);
+}
+
+#[test]
+fn parse_any_timestamp_keeps_fractions() {
+    let ms = |s| parse_any_timestamp(s, Precision::Millis).unwrap();
+    assert_eq!(ms("2024-01-10 12:00:00.5Z"), Timestamp::new(1704888000500, Precision::Millis));
+    assert_eq!(ms("2024-01-10T12:00:00.25Z"), Timestamp::new(1704888000250, Precision::Millis));
+    assert_eq!(ms("1704888000123"), Timestamp::new(1704888000123, Precision::Millis));
+    assert_eq!(ms("1969-12-31 23:59:59.5Z"), Timestamp::new(-500, Precision::Millis));
+    assert_eq!(parse_any("2024-01-10 12:00:00.5Z").unwrap(), 1704888000);
+    let rfc3339 = InputFormat::Rfc3339.parse_timestamp("2024-01-10 12:00:00.123456Z", Precision::Micros);
+    assert_eq!(rfc3339.unwrap(), Timestamp::new(1704888000123456, Precision::Micros));
+}
+
+#[test]
+fn parse_any_reports_what_it_tried() {
+    let err = parse_any("next tuesday").unwrap_err();
+    assert!(matches!(err, EtError::UnrecognizedTimestamp(_)));
+    assert_eq!(
+        err.to_string(),
+        "unrecognized timestamp: next tuesday (tried epoch, ISO-8601, RFC 2822, RFC 3339 with a space separator, and YYYY-MM-DD)"
+    );
+    // ISO-8601 that only lacks a timezone says so
+    assert!(matches!(parse_any("2024-01-10T12:00:00"), Err(EtError::MissingTimezone(_))));
+}
+
+#[test]
+fn input_format_parses_only_its_own_format() {
+    assert_eq!("rfc2822".parse::<InputFormat>().unwrap(), InputFormat::Rfc2822);
+    assert!(matches!("unix".parse::<InputFormat>(), Err(EtError::InvalidFormat(_))));
+    assert_eq!(InputFormat::Rfc3339.parse("2024-01-10T12:00:00Z").unwrap(), 1704888000);
+    assert!(InputFormat::Date.parse("1704888000").is_err());
+    assert!(InputFormat::Epoch.parse("2024-01-10").is_err());
+    assert!(InputFormat::Rfc2822.parse("2024-01-10T12:00:00Z").is_err());
+    assert!(InputFormat::Rfc3339.parse("2024-01-10t12:00:00z").is_err());
+}
+
+#[test]
+fn parse_with_named_formats_and_patterns() {
+    assert_eq!(parse_with("2024-01-10", "date").unwrap(), 1704844800);
+    assert_eq!(parse_with("1704888000", "epoch").unwrap(), 1704888000);
+    // Without a time of day, midnight; without an offset, UTC
+    assert_eq!(parse_with("01/10/2024", "[month]/[day]/[year]").unwrap(), 1704844800);
+    assert_eq!(parse_with(" 01/10/2024 12:00 ", "[month]/[day]/[year] [hour]:[minute]").unwrap(), 1704888000);
+    let with_offset = "[year]-[month]-[day] [hour]:[minute] [offset_hour sign:mandatory]:[offset_minute]";
+    assert_eq!(parse_with("2024-01-10 14:00 +02:00", with_offset).unwrap(), 1704888000);
+    // Reverses format_with
+    let pattern = "[day].[month].[year] [hour]:[minute]:[second]";
+    assert_eq!(parse_with(&format_with(-1, pattern).unwrap(), pattern).unwrap(), -1);
+
+    assert!(matches!(parse_with("01/10/2024", "[month"), Err(EtError::InvalidFormat(_))));
+    assert!(matches!(parse_with("01/10/2024 extra", "[month]/[day]/[year]"), Err(EtError::InvalidDate(_))));
+    assert!(matches!(parse_with("13/10/2024", "[month]/[day]/[year]"), Err(EtError::InvalidDate(_))));
+    assert!(parse_with("01/10", "[month]/[day]").is_err());
+}
+
+// Format
+#[test]
+fn format_epoch() {
+    assert_eq!(format_iso(0).unwrap(), "1970-01-01T00:00:00Z");
+    assert_eq!(format_iso(1704888000).unwrap(), "2024-01-10T12:00:00Z");
+    assert_eq!(format_iso(-86400).unwrap(), "1969-12-31T00:00:00Z");
+}
+
+#[test]
+fn write_iso_matches_format_iso() {
+    let mut buf = Vec::new();
+    write_iso(&mut buf, 1704888000).unwrap();
+    assert_eq!(buf, format_iso(1704888000).unwrap().as_bytes());
+}
+
+#[test]
+fn write_many_into_one_buffer() {
+    let mut buf = Vec::with_capacity(100_000 * 21);
+    for i in 0..100_000i64 {
+        write_iso(&mut buf, i * 3600).unwrap();
+        buf.push(b'\n');
+    }
+    assert_eq!(buf.len(), 100_000 * 21);
+    let text = String::from_utf8(buf).unwrap();
+    assert_eq!(text.lines().next(), Some("1970-01-01T00:00:00Z"));
+    assert_eq!(text.lines().last(), Some(format_iso(99_999 * 3600).unwrap().as_str()));
+
+    let mut buf = Vec::new();
+    write_epoch(&mut buf, -86400).unwrap();
+    assert_eq!(buf, b"-86400");
+}
+
+#[test]
+fn write_iso_out_of_range() {
+    let mut buf = Vec::new();
+    assert!(write_iso(&mut buf, i64::MAX).is_err());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn epochs_past_the_calendar_range_are_out_of_range() {
+    assert_eq!(format_iso(MAX_EPOCH).unwrap(), "9999-12-31T23:59:59Z");
+    assert_eq!(format_iso(MIN_EPOCH).unwrap(), "-9999-01-01T00:00:00Z");
+    assert!(matches!(format_iso(MAX_EPOCH + 1), Err(EtError::EpochOutOfRange(e)) if e == MAX_EPOCH + 1));
+    assert!(matches!(decompose(MAX_EPOCH + 1), Err(EtError::EpochOutOfRange(_))));
+    assert!(matches!(decompose(MIN_EPOCH - 1), Err(EtError::EpochOutOfRange(_))));
+    let millis = Timestamp::new((MAX_EPOCH as i128 + 1) * 1000, Precision::Millis);
+    assert!(matches!(millis.format_iso(), Err(EtError::EpochOutOfRange(_))));
+    // A malformed epoch is still a different error
+    assert!(matches!(parse_epoch("12x"), Err(EtError::InvalidEpoch(_))));
+    let message = EtError::EpochOutOfRange(MAX_EPOCH + 1).to_string();
+    assert!(message.contains("-377705116800") && message.contains("253402300799"), "{message}");
+}
+
+#[test]
+fn format_with_named_styles() {
+    assert_eq!(format_with(1704888000, "iso").unwrap(), "2024-01-10T12:00:00Z");
+    assert_eq!(format_with(1704888000, "rfc3339").unwrap(), "2024-01-10T12:00:00Z");
+    assert_eq!(format_with(1704888000, "rfc2822").unwrap(), "Wed, 10 Jan 2024 12:00:00 +0000");
+    assert_eq!(format_with(1704888000, "iso-basic").unwrap(), "20240110T120000Z");
+}
+
+#[test]
+fn format_with_raw_pattern() {
+    assert_eq!(format_with(1704888000, "[year]/[month]/[day] [hour]h").unwrap(), "2024/01/10 12h");
+    assert!(matches!(format_with(0, "[bogus]"), Err(EtError::InvalidFormat(_))));
+    // RFC 2822 cannot express years before 1900
+    assert!(matches!(format_with(-99999999999, "rfc2822"), Err(EtError::InvalidFormat(_))));
+}
+
+#[test]
+fn format_with_at_offset() {
+    let ts = Timestamp::from_seconds(1704888000);
+    let pst = parse_offset("-08:00").unwrap();
+    assert_eq!(ts.format_with_at("iso", pst).unwrap(), "2024-01-10T04:00:00-08:00");
+    assert_eq!(ts.format_with_at("iso-basic", pst).unwrap(), "20240110T040000-0800");
+    assert_eq!(ts.format_with_at("rfc3339", pst).unwrap(), "2024-01-10T04:00:00-08:00");
+    assert_eq!(ts.format_with_at("[hour]", pst).unwrap(), "04");
+    assert_eq!(ts.format_with_at("iso", UtcOffset::UTC).unwrap(), "2024-01-10T12:00:00Z");
+    let odd = parse_offset("-05:30:15").unwrap();
+    assert_eq!(ts.format_with_at("iso", odd).unwrap(), "2024-01-10T06:29:45-05:30:15");
+    assert_eq!(ts.format_with_at("iso-basic", odd).unwrap(), "20240110T062945-053015");
+    assert_eq!(format_iso_at(1704888000, odd).unwrap(), "2024-01-10T06:29:45-05:30:15");
+}
+
+// Precision
+#[test]
+fn precision_conversions() {
+    let ts = Timestamp::from_seconds(1704888000);
+    assert_eq!(ts.to_precision(Precision::Millis).unwrap().value, 1704888000000);
+    assert_eq!(ts.to_precision(Precision::Micros).unwrap().value, 1704888000000000);
+    assert_eq!(ts.to_precision(Precision::Nanos).unwrap().value, 1704888000000000000);
+
+    let ns = Timestamp::from_nanos(1704888000123456789);
+    assert_eq!(ns.to_precision(Precision::Micros).unwrap().value, 1704888000123456);
+    assert_eq!(ns.to_precision(Precision::Millis).unwrap().value, 1704888000123);
+    assert_eq!(ns.as_seconds().unwrap(), 1704888000);
+}
+
+#[test]
+fn precision_truncates_towards_earlier_tick() {
+    let ms = Timestamp::new(-1, Precision::Millis);
+    assert_eq!(ms.as_seconds().unwrap(), -1);
+    assert_eq!(ms.to_precision(Precision::Seconds).unwrap().value, -1);
+}
+
+#[test]
+fn precision_overflow() {
+    let ts = Timestamp::new(i128::MAX / 10, Precision::Seconds);
+    assert!(matches!(ts.to_precision(Precision::Nanos), Err(EtError::Overflow)));
+    assert!(matches!(ts.as_seconds(), Err(EtError::Overflow)));
+}
+
+#[test]
+fn precision_from_str() {
+    assert_eq!("s".parse::<Precision>().unwrap(), Precision::Seconds);
+    assert_eq!("ms".parse::<Precision>().unwrap(), Precision::Millis);
+    assert_eq!("micros".parse::<Precision>().unwrap(), Precision::Micros);
+    assert_eq!("ns".parse::<Precision>().unwrap(), Precision::Nanos);
+    assert!(matches!("ps".parse::<Precision>(), Err(EtError::UnsupportedPrecision(_))));
+}
+
+#[test]
+fn precision_guessed_from_digit_count() {
+    assert_eq!(Precision::guess("1704888000"), Precision::Seconds);
+    assert_eq!(Precision::guess("0"), Precision::Seconds);
+    assert_eq!(Precision::guess("1704888000123"), Precision::Millis);
+    assert_eq!(Precision::guess("-1704888000123"), Precision::Millis);
+    assert_eq!(Precision::guess("1704888000123456"), Precision::Micros);
+    assert_eq!(Precision::guess("1704888000123456789"), Precision::Nanos);
+    // One digit either side still counts
+    assert_eq!(Precision::guess("17048880001"), Precision::Seconds);
+    assert_eq!(Precision::guess("170488800012"), Precision::Millis);
+}
+
+#[test]
+fn format_at_each_precision() {
+    let ns = Timestamp::from_nanos(1704888000123456789);
+    let at = |p| ns.to_precision(p).unwrap().format_iso().unwrap();
+    assert_eq!(at(Precision::Seconds), "2024-01-10T12:00:00Z");
+    assert_eq!(at(Precision::Millis), "2024-01-10T12:00:00.123Z");
+    assert_eq!(at(Precision::Micros), "2024-01-10T12:00:00.123456Z");
+    assert_eq!(at(Precision::Nanos), "2024-01-10T12:00:00.123456789Z");
+}
+
+#[test]
+fn parse_at_precision() {
+    let ts = Timestamp::parse_epoch(" 1704888000123 ", Precision::Millis).unwrap();
+    assert_eq!(ts, Timestamp::new(1704888000123, Precision::Millis));
+
+    let ts = Timestamp::parse_iso("2024-01-10T12:00:00.123456Z", Precision::Micros).unwrap();
+    assert_eq!(ts.value, 1704888000123456);
+    assert!(Timestamp::parse_epoch("12.5", Precision::Millis).is_err());
+}
+
+#[test]
+fn apply_keeps_subsecond_part() {
+    let ts = Timestamp::new(1704888000123, Precision::Millis);
+    let hour = Duration::parse("+1h").unwrap();
+    assert_eq!(ts.apply(hour).unwrap().value, 1704891600123);
+
+    let month = Duration::parse("+1M").unwrap();
+    let next = ts.apply(month).unwrap();
+    assert_eq!(next.format_iso().unwrap(), "2024-02-10T12:00:00.123Z");
+
+    let ms = Timestamp::new(-1, Precision::Millis);
+    assert_eq!(ms.apply(Duration::Seconds(1)).unwrap().value, 999);
+}
+
+// Relative Time
+/// Fixed clock for relative-time tests: 2024-01-10T12:00:00Z
+const NOW: i64 = 1704888000;
+
+#[test]
+fn parse_timestamp_accepts_epoch_and_iso() {
+    assert_eq!(parse_timestamp("1704888000").unwrap(), NOW);
+    assert_eq!(parse_timestamp("2024-01-10T12:00:00Z").unwrap(), NOW);
+    assert!(parse_timestamp("yesterday").is_err());
+}
+
+#[test]
+fn since_past_and_future() {
+    let past = parse_iso("2024-01-09T12:00:00Z").unwrap();
+    let future = parse_iso("2024-01-10T15:00:00Z").unwrap();
+    assert_eq!(since(past, NOW).unwrap(), 86400);
+    assert_eq!(since(future, NOW).unwrap(), -10800);
+}
+
+#[test]
+fn until_past_and_future() {
+    let past = parse_iso("2024-01-09T12:00:00Z").unwrap();
+    let future = parse_iso("2024-01-10T15:00:00Z").unwrap();
+    assert_eq!(until(future, NOW).unwrap(), 10800);
+    assert_eq!(until(past, NOW).unwrap(), -86400);
+}
+
+#[test]
+fn since_until_overflow() {
+    assert!(matches!(since(i64::MIN, 1).unwrap_err(), EtError::Overflow));
+    assert!(matches!(until(i64::MAX, -1).unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn humanize_relative_largest_unit() {
+    assert_eq!(humanize_relative(NOW, NOW), "now");
+    assert_eq!(humanize_relative(NOW - 1, NOW), "1 second ago");
+    assert_eq!(humanize_relative(NOW - 3 * 3600 - 59, NOW), "3 hours ago");
+    assert_eq!(humanize_relative(NOW + 2 * 86400, NOW), "in 2 days");
+    assert_eq!(humanize_relative(NOW + 14 * 86400, NOW), "in 2 weeks");
+    assert_eq!(humanize_relative(NOW - 400 * 86400, NOW), "1 year ago");
+}
+
+#[test]
+fn humanize_relative_abbrev_uses_compact_units() {
+    assert_eq!(humanize_relative_abbrev(NOW, NOW), "now");
+    assert_eq!(humanize_relative_abbrev(NOW - 1, NOW), "1s ago");
+    assert_eq!(humanize_relative_abbrev(NOW - 3 * 3600 - 59, NOW), "3h ago");
+    assert_eq!(humanize_relative_abbrev(NOW + 2 * 86400, NOW), "in 2d");
+    assert_eq!(humanize_relative_abbrev(NOW - 45 * 86400, NOW), "1mo ago");
+    assert_eq!(humanize_relative_abbrev(NOW - 400 * 86400, NOW), "1y ago");
+    assert_ne!(humanize_relative_abbrev(NOW - 60, NOW), humanize_relative(NOW - 60, NOW));
+}
+
+#[test]
+fn humanize_relative_parts_shows_the_largest_units() {
+    let delta = 86400 + 3 * 3600 + 5 * 60;
+    assert_eq!(humanize_relative_parts(NOW - delta, NOW, 1, false), humanize_relative(NOW - delta, NOW));
+    assert_eq!(humanize_relative_parts(NOW - delta, NOW, 2, false), "1 day 3 hours ago");
+    assert_eq!(humanize_relative_parts(NOW + delta, NOW, 3, false), "in 1 day 3 hours 5 minutes");
+    assert_eq!(humanize_relative_parts(NOW + delta, NOW, 10, true), "in 1d 3h 5m");
+    // Zero units are skipped, and zero parts counts as one
+    assert_eq!(humanize_relative_parts(NOW - 86405, NOW, 2, false), "1 day 5 seconds ago");
+    assert_eq!(humanize_relative_parts(NOW - delta, NOW, 0, true), "1d ago");
+    assert_eq!(humanize_relative_parts(NOW, NOW, 2, false), "now");
+}
+
+#[test]
+fn humanize_relative_extremes_do_not_overflow() {
+    assert!(humanize_relative(i64::MIN, i64::MAX).ends_with("ago"));
+    assert!(humanize_relative(i64::MAX, i64::MIN).starts_with("in "));
+}
+
+// Duration Humanizing
+#[test]
+fn humanize_duration_multi_unit() {
+    assert_eq!(humanize_duration(90061), "1d 1h 1m 1s");
+    assert_eq!(humanize_duration(86400 * 10 + 30), "10d 30s");
+    assert_eq!(humanize_duration(3600), "1h");
+}
+
+#[test]
+fn humanize_duration_zero_and_negative() {
+    assert_eq!(humanize_duration(0), "0s");
+    assert_eq!(humanize_duration(-3661), "-1h 1m 1s");
+    assert_eq!(humanize_duration(-59), "-59s");
+    assert_eq!(humanize_duration(i64::MIN), "-106751991167300d 15h 30m 8s");
+}
+
+#[test]
+fn epoch_unit_suffixes() {
+    assert_eq!(parse_epoch("1704912345s").unwrap(), 1704912345);
+    assert_eq!(parse_epoch("1704912345000ms").unwrap(), 1704912345);
+    assert_eq!(parse_epoch("1704912345999ms").unwrap(), 1704912345);
+    let millis = Timestamp::parse_epoch("1704912345s", Precision::Millis).unwrap();
+    assert_eq!(millis, Timestamp::new(1704912345000, Precision::Millis));
+    let nanos = Timestamp::parse_epoch("1704912345123456us", Precision::Nanos).unwrap();
+    assert_eq!(nanos.value, 1704912345123456000);
+    assert_eq!(Timestamp::parse_epoch("-5ns", Precision::Nanos).unwrap().value, -5);
+    // Without a suffix the number is counted at the requested precision
+    assert_eq!(Timestamp::parse_epoch("1704912345000", Precision::Millis).unwrap().value, 1704912345000);
+    assert!(matches!(parse_epoch("1704912345ks"), Err(EtError::InvalidEpoch(_))));
+    assert!(matches!(parse_epoch("ms"), Err(EtError::InvalidEpoch(_))));
+}
+
+#[test]
+fn epoch_keywords_mean_zero() {
+    for keyword in ["epoch", "unix", "zero", "UNIX", " Epoch "] {
+        assert!(is_epoch_keyword(keyword));
+        assert_eq!(parse_epoch(keyword).unwrap(), 0);
+        assert_eq!(parse_iso(keyword).unwrap(), 0);
+        assert_eq!(parse_timestamp(keyword).unwrap(), 0);
+        assert_eq!(parse_iso_assuming(keyword, parse_offset("+05:00").unwrap()).unwrap(), 0);
+        assert_eq!(Timestamp::parse_epoch(keyword, Precision::Millis).unwrap().value, 0);
+    }
+    assert!(!is_epoch_keyword("origin"));
+    assert!(matches!(parse_epoch("origin"), Err(EtError::InvalidEpoch(_))));
+    assert!(parse_iso("origin").is_err());
+}
+
+#[test]
+fn timestamp_try_from_epoch_and_iso() {
+    let epoch = Timestamp::try_from("1704888000").unwrap();
+    let iso = Timestamp::try_from("2024-01-10T12:00:00.999Z".to_string()).unwrap();
+    assert_eq!(epoch, Timestamp::from_seconds(1704888000));
+    assert_eq!(iso, epoch);
+    assert!(matches!(Timestamp::try_from("yesterday"), Err(EtError::MissingTimezone(_))));
+    assert!(Timestamp::try_from("2024-01-10T12:00:00").is_err());
+}
+
+#[test]
+fn timestamp_conversions_and_display() {
+    let ts = Timestamp::from(1704888000);
+    assert_eq!(i64::try_from(ts).unwrap(), 1704888000);
+    assert_eq!(ts.to_string(), "2024-01-10T12:00:00Z");
+    let dt = OffsetDateTime::try_from(ts).unwrap();
+    assert_eq!(dt.unix_timestamp(), 1704888000);
+    assert_eq!(Timestamp::from(dt), Timestamp::from_nanos(1704888000000000000));
+    assert_eq!(Timestamp::new(1704888000123, Precision::Millis).to_string(), "2024-01-10T12:00:00.123Z");
+    assert_eq!(Timestamp::from(i64::MAX).to_string(), i64::MAX.to_string());
+}
+
+// Color
+#[test]
+fn color_iso_splits_date_and_time() {
+    let colored = color_iso("2024-01-10T12:00:00Z");
+    assert_eq!(colored, "\x1b[34m2024-01-10\x1b[0mT\x1b[32m12:00:00Z\x1b[0m");
+    assert_eq!(color_iso("not a timestamp"), "not a timestamp");
+}
+
+#[test]
+fn color_relative_words() {
+    assert_eq!(color_relative("3 hours ago"), "3 hours \x1b[33mago\x1b[0m");
+    assert_eq!(color_relative("in 2 days"), "\x1b[33min\x1b[0m 2 days");
+    assert_eq!(color_relative("now"), "\x1b[33mnow\x1b[0m");
+}
+
+// Ranges and Sequences
+/// Helper: collect a sequence over `range` into a Vec, panicking on error
+fn seq(range: &str, step: &str) -> Vec<i64> {
+    let range = EpochRange::parse(range).unwrap();
+    let step = Duration::parse(step).unwrap();
+    sequence(range, step).unwrap().map(Result::unwrap).collect()
+}
+
+#[test]
+fn parse_range_syntax() {
+    let half_open = EpochRange::parse("0..86400").unwrap();
+    assert_eq!(half_open, EpochRange { start: 0, end: 86400, inclusive: false });
+    let inclusive = EpochRange::parse("0..=86400").unwrap();
+    assert!(inclusive.inclusive);
+    let iso = EpochRange::parse("2024-01-10T12:00:00Z..2024-01-11T12:00:00Z").unwrap();
+    assert_eq!((iso.start, iso.end), (1704888000, 1704974400));
+}
+
+#[test]
+fn parse_range_invalid() {
+    assert!(matches!(EpochRange::parse("0-86400").unwrap_err(), EtError::InvalidRange(_)));
+    assert!(matches!(EpochRange::parse("..86400").unwrap_err(), EtError::InvalidRange(_)));
+    assert!(matches!(EpochRange::parse("0..").unwrap_err(), EtError::InvalidRange(_)));
+    assert!(EpochRange::parse("0..later").is_err());
+}
+
+#[test]
+fn sequence_half_open_excludes_end() {
+    assert_eq!(seq("0..259200", "1d"), vec![0, 86400, 172800]);
+    assert_eq!(seq("0..259201", "1d"), vec![0, 86400, 172800, 259200]);
 }
 
 #[test]
-fn duration_months_word_forms() {
-    assert_eq!(Duration::parse("1mo").unwrap(), Duration::Months(1));
-    assert_eq!(Duration::parse("2month").unwrap(), Duration::Months(2));
-    assert_eq!(Duration::parse("3months").unwrap(), Duration::Months(3));
+fn sequence_inclusive_includes_end() {
+    assert_eq!(seq("0..=259200", "1d"), vec![0, 86400, 172800, 259200]);
+    assert_eq!(seq("0..=0", "1h"), vec![0]);
+    assert_eq!(seq("0..0", "1h"), Vec::<i64>::new());
 }
 
 #[test]
-fn duration_years() {
-    assert_eq!(Duration::parse("1Y").unwrap(), Duration::Years(1));
-    assert_eq!(Duration::parse("+2Y").unwrap(), Duration::Years(2));
-    assert_eq!(Duration::parse("-1Y").unwrap(), Duration::Years(-1));
-    assert_eq!(Duration::parse("1y").unwrap(), Duration::Years(1));
-    assert_eq!(Duration::parse("1yr").unwrap(), Duration::Years(1));
-    assert_eq!(Duration::parse("2year").unwrap(), Duration::Years(2));
-    assert_eq!(Duration::parse("3years").unwrap(), Duration::Years(3));
+fn sequence_descending() {
+    assert_eq!(seq("3600..=0", "-30m"), vec![3600, 1800, 0]);
 }
 
 #[test]
-fn duration_large_calendar_values() {
-    assert_eq!(Duration::parse("100M").unwrap(), Duration::Months(100));
-    assert_eq!(Duration::parse("50Y").unwrap(), Duration::Years(50));
+fn sequence_calendar_step_does_not_drift() {
+    let months: Vec<String> = seq("2024-01-31T00:00:00Z..2024-05-01T00:00:00Z", "1M")
+        .into_iter()
+        .map(|e| format_iso(e).unwrap())
+        .collect();
+    assert_eq!(
+        months,
+        ["2024-01-31T00:00:00Z", "2024-02-29T00:00:00Z", "2024-03-31T00:00:00Z", "2024-04-30T00:00:00Z"]
+    );
 }
 
-// Duration Parsing - Invalid Input
 #[test]
-fn duration_invalid_formats() {
-    assert!(Duration::parse("").is_err());
-    assert!(Duration::parse("abc").is_err());
-    assert!(Duration::parse("++5s").is_err());
-    assert!(Duration::parse("--5s").is_err());
-    assert!(Duration::parse("+").is_err());
-    assert!(Duration::parse("-").is_err());
+fn sequence_rejects_bad_steps() {
+    let range = EpochRange::parse("0..86400").unwrap();
+    assert!(matches!(sequence(range, Duration::Seconds(0)).unwrap_err(), EtError::InvalidDuration(_)));
+    assert!(matches!(sequence(range, Duration::Seconds(-1)).unwrap_err(), EtError::InvalidRange(_)));
+    assert!(matches!(sequence(range, Duration::Months(0)).unwrap_err(), EtError::InvalidDuration(_)));
 }
 
+// Periods
+// 2024-01-10T12:34:56Z, a Wednesday
+const MID_WEEK: i64 = 1704890096;
+
 #[test]
-fn duration_unknown_unit() {
-    assert!(matches!(Duration::parse("5x").unwrap_err(), EtError::UnsupportedUnit(_)));
-    assert!(matches!(Duration::parse("10foo").unwrap_err(), EtError::UnsupportedUnit(_)));
+fn start_of_each_period() {
+    let start = |p| start_of(MID_WEEK, p, WeekStart::Monday).unwrap();
+    assert_eq!(start(Period::Minute), 1704890040);
+    assert_eq!(start(Period::Hour), 1704888000);
+    assert_eq!(start(Period::Day), 1704844800);
+    assert_eq!(start(Period::Week), 1704672000); // Monday 2024-01-08
+    assert_eq!(start(Period::Month), 1704067200);
+    assert_eq!(start(Period::Year), 1704067200);
 }
 
-// Epoch Parsing
 #[test]
-fn parse_epoch_valid() {
-    assert_eq!(parse_epoch("1704912345").unwrap(), 1704912345);
-    assert_eq!(parse_epoch("0").unwrap(), 0);
-    assert_eq!(parse_epoch("-1000").unwrap(), -1000);
-    assert_eq!(parse_epoch("  1704912345  ").unwrap(), 1704912345);
+fn end_of_each_period() {
+    let end = |p| end_of(MID_WEEK, p, WeekStart::Monday).unwrap();
+    assert_eq!(end(Period::Minute), 1704890099);
+    assert_eq!(end(Period::Day), 1704931199);
+    assert_eq!(end(Period::Week), 1705276799); // Sunday 2024-01-14T23:59:59Z
+    assert_eq!(end(Period::Month), 1706745599);
+    assert_eq!(end(Period::Year), 1735689599);
 }
 
 #[test]
-fn parse_epoch_invalid() {
-    assert!(parse_epoch("abc").is_err());
-    assert!(parse_epoch("12.34").is_err());
-    assert!(parse_epoch("").is_err());
+fn next_start_of_is_one_past_end_of() {
+    for period in [Period::Minute, Period::Day, Period::Week, Period::Month, Period::Quarter, Period::Year] {
+        let end = end_of(MID_WEEK, period, WeekStart::Monday).unwrap();
+        assert_eq!(next_start_of(MID_WEEK, period, WeekStart::Monday).unwrap(), end + 1, "{period:?}");
+    }
+    // 2024-02-01T00:00:00Z, the start of the month after January
+    assert_eq!(next_start_of(MID_WEEK, Period::Month, WeekStart::Monday).unwrap(), 1706745600);
 }
 
-// ISO Parsing
 #[test]
-fn parse_iso_utc() {
-    assert_eq!(parse_iso("2024-01-10T12:00:00Z").unwrap(), 1704888000);
+fn quarter_periods() {
+    let quarter = |iso: &str| {
+        let epoch = parse_iso(iso).unwrap();
+        let start = start_of(epoch, Period::Quarter, WeekStart::Monday).unwrap();
+        let end = end_of(epoch, Period::Quarter, WeekStart::Monday).unwrap();
+        (format_iso(start).unwrap(), format_iso(end).unwrap())
+    };
+    assert_eq!(quarter("2024-01-01T00:00:00Z"), ("2024-01-01T00:00:00Z".into(), "2024-03-31T23:59:59Z".into()));
+    assert_eq!(quarter("2024-05-15T12:00:00Z"), ("2024-04-01T00:00:00Z".into(), "2024-06-30T23:59:59Z".into()));
+    assert_eq!(quarter("2024-12-31T23:59:59Z"), ("2024-10-01T00:00:00Z".into(), "2024-12-31T23:59:59Z".into()));
+    assert_eq!("quarter".parse::<Period>().unwrap(), Period::Quarter);
 }
 
 #[test]
-fn parse_iso_with_offset() {
-    let z = parse_iso("2024-01-10T12:00:00Z").unwrap();
-    let zero = parse_iso("2024-01-10T12:00:00+00:00").unwrap();
-    assert_eq!(z, zero);
+fn week_start_monday_and_sunday() {
+    assert_eq!(start_of(MID_WEEK, Period::Week, WeekStart::Monday).unwrap(), 1704672000);
+    assert_eq!(start_of(MID_WEEK, Period::Week, WeekStart::Sunday).unwrap(), 1704585600);
+    assert_eq!(end_of(MID_WEEK, Period::Week, WeekStart::Monday).unwrap(), 1705276799);
+    assert_eq!(end_of(MID_WEEK, Period::Week, WeekStart::Sunday).unwrap(), 1705190399);
 
-    // +02:00 means 2 hours ahead, so UTC is 2 hours earlier
-    let plus2 = parse_iso("2024-01-10T12:00:00+02:00").unwrap();
-    let expected = parse_iso("2024-01-10T10:00:00Z").unwrap();
-    assert_eq!(plus2, expected);
+    // A Sunday starts its own week for US calendars but ends the ISO week
+    let sunday = 1704585600 + 3600;
+    assert_eq!(start_of(sunday, Period::Week, WeekStart::Sunday).unwrap(), 1704585600);
+    assert_eq!(start_of(sunday, Period::Week, WeekStart::Monday).unwrap(), 1704067200);
 }
 
 #[test]
-fn parse_iso_missing_timezone() {
-    assert!(matches!(
-        parse_iso("2024-01-10T12:00:00").unwrap_err(),
-        EtError::MissingTimezone(_)
-    ));
+fn start_of_before_epoch() {
+    assert_eq!(start_of(-1, Period::Day, WeekStart::Monday).unwrap(), -86400);
+    assert_eq!(start_of(-1, Period::Year, WeekStart::Monday).unwrap(), -31536000);
 }
 
 #[test]
-fn parse_iso_invalid() {
-    assert!(parse_iso("not-a-date").is_err());
-    assert!(parse_iso("2024-13-01T00:00:00Z").is_err()); // invalid month
-    assert!(parse_iso("2024-01-32T00:00:00Z").is_err()); // invalid day
+fn period_and_week_start_from_str() {
+    assert_eq!("week".parse::<Period>().unwrap(), Period::Week);
+    assert!(matches!("fortnight".parse::<Period>(), Err(EtError::UnsupportedPeriod(_))));
+    assert_eq!("sun".parse::<WeekStart>().unwrap(), WeekStart::Sunday);
+    assert_eq!("monday".parse::<WeekStart>().unwrap(), WeekStart::Monday);
+    assert!(matches!("sat".parse::<WeekStart>(), Err(EtError::InvalidWeekStart(_))));
 }
 
-// Format
 #[test]
-fn format_epoch() {
-    assert_eq!(format_iso(0).unwrap(), "1970-01-01T00:00:00Z");
-    assert_eq!(format_iso(1704888000).unwrap(), "2024-01-10T12:00:00Z");
-    assert_eq!(format_iso(-86400).unwrap(), "1969-12-31T00:00:00Z");
+fn sequence_len_fixed_steps() {
+    let range = |s| EpochRange::parse(s).unwrap();
+    assert_eq!(sequence_len(range("0..10"), Duration::Seconds(5)).unwrap(), 2);
+    assert_eq!(sequence_len(range("0..=10"), Duration::Seconds(5)).unwrap(), 3);
+    assert_eq!(sequence_len(range("0..11"), Duration::Seconds(5)).unwrap(), 3);
+    assert_eq!(sequence_len(range("10..0"), Duration::Seconds(-3)).unwrap(), 4);
+    assert_eq!(sequence_len(range("5..5"), Duration::Seconds(1)).unwrap(), 0);
+    assert_eq!(sequence_len(range("5..=5"), Duration::Seconds(1)).unwrap(), 1);
+    assert_eq!(sequence_len(range("0..86400000"), Duration::Seconds(1)).unwrap(), 86400000);
+    assert!(sequence_len(range("0..10"), Duration::Seconds(0)).is_err());
+    assert!(sequence_len(range("0..10"), Duration::Seconds(-1)).is_err());
+}
+
+#[test]
+fn sequence_len_calendar_steps() {
+    let range = EpochRange::parse("2024-01-31T00:00:00Z..=2024-12-31T00:00:00Z").unwrap();
+    let expected = sequence(range, Duration::Months(1)).unwrap().count() as u64;
+    assert_eq!(sequence_len(range, Duration::Months(1)).unwrap(), expected);
+    assert_eq!(expected, 12);
+}
+
+// Counting Steps
+#[test]
+fn count_months_across_a_year_boundary() {
+    let from = parse_iso("2023-11-15T00:00:00Z").unwrap();
+    let to = parse_iso("2024-03-14T00:00:00Z").unwrap();
+    assert_eq!(count_steps(from, to, Duration::Months(1)).unwrap(), 3);
+    assert_eq!(count_steps(to, from, Duration::Months(1)).unwrap(), -3);
+    let to = parse_iso("2024-03-15T00:00:00Z").unwrap();
+    assert_eq!(count_steps(from, to, Duration::Months(1)).unwrap(), 4);
+    // Clamped steps still count: Nov 30 + 3 months is Feb 29
+    let from = parse_iso("2023-11-30T00:00:00Z").unwrap();
+    let to = parse_iso("2024-02-29T00:00:00Z").unwrap();
+    assert_eq!(count_steps(from, to, Duration::Months(1)).unwrap(), 3);
+}
+
+#[test]
+fn count_fixed_steps() {
+    assert_eq!(count_steps(0, 86400 * 10, Duration::Seconds(86400)).unwrap(), 10);
+    assert_eq!(count_steps(0, 86400 * 10 - 1, Duration::Seconds(86400)).unwrap(), 9);
+    assert_eq!(count_steps(0, 86400 * 10, Duration::Seconds(-86400)).unwrap(), 10);
+    assert!(matches!(count_steps(0, 10, Duration::Seconds(0)), Err(EtError::InvalidDuration(_))));
+}
+
+// Rounding to Multiples
+#[test]
+fn floor_and_ceil_mid_interval() {
+    let quarter = Duration::Seconds(900);
+    // 2024-01-10T18:45:45Z
+    assert_eq!(floor_to(1704912345, quarter).unwrap(), 1704912300);
+    assert_eq!(ceil_to(1704912345, quarter).unwrap(), 1704913200);
+    assert_eq!(floor_to(-1, quarter).unwrap(), -900);
+    assert_eq!(ceil_to(-1, quarter).unwrap(), 0);
+}
+
+#[test]
+fn floor_and_ceil_aligned() {
+    let quarter = Duration::Seconds(900);
+    assert_eq!(floor_to(1704912300, quarter).unwrap(), 1704912300);
+    assert_eq!(ceil_to(1704912300, quarter).unwrap(), 1704912300);
+    assert_eq!(ceil_to(-900, quarter).unwrap(), -900);
+    assert!(matches!(floor_to(0, Duration::Months(1)), Err(EtError::UnsupportedUnit(_))));
+    assert!(matches!(ceil_to(0, Duration::Seconds(-900)), Err(EtError::InvalidDuration(_))));
+}
+
+#[test]
+fn require_fixed_rejects_calendar_durations() {
+    assert_eq!(require_fixed(Duration::parse("15m").unwrap()).unwrap(), 900);
+    assert_eq!(require_fixed(Duration::Seconds(-5)).unwrap(), -5);
+    let e = require_fixed(Duration::parse("+1M").unwrap()).unwrap_err();
+    assert!(matches!(e, EtError::UnsupportedUnit(_)));
+    assert_eq!(e.to_string(), "unsupported unit: 1M (months and years have no fixed length; use s, m, h, d, or w)");
+    assert!(require_fixed(Duration::Years(1)).is_err());
+}
+
+// Random Timestamps
+#[test]
+fn random_epochs_stay_in_range() {
+    let (from, to) = (1577836800, 1735689600);
+    for epoch in RandomEpochs::new(from, to, 7).unwrap().take(1000) {
+        assert!((from..to).contains(&epoch), "{epoch}");
+    }
+
+    let all: Vec<i64> = RandomEpochs::new(i64::MIN, i64::MAX, 7).unwrap().take(100).collect();
+    assert!(all.iter().any(|&e| e < 0) && all.iter().any(|&e| e > 0));
+}
+
+#[test]
+fn random_epochs_are_reproducible() {
+    let a: Vec<i64> = RandomEpochs::new(0, 1_000_000, 42).unwrap().take(10).collect();
+    let b: Vec<i64> = RandomEpochs::new(0, 1_000_000, 42).unwrap().take(10).collect();
+    let c: Vec<i64> = RandomEpochs::new(0, 1_000_000, 43).unwrap().take(10).collect();
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+}
+
+#[test]
+fn random_epochs_need_from_before_to() {
+    assert!(matches!(RandomEpochs::new(10, 10, 0), Err(EtError::InvalidRange(_))));
+    assert!(matches!(RandomEpochs::new(11, 10, 0), Err(EtError::InvalidRange(_))));
+    assert_eq!(RandomEpochs::new(10, 11, 0).unwrap().next(), Some(10));
+}
+
+// Components
+#[test]
+fn decompose_known_epoch() {
+    // 2024-01-10T18:45:45Z, a Wednesday
+    let c = decompose(1704912345).unwrap();
+    assert_eq!(c.year, 2024);
+    assert_eq!(c.month, 1);
+    assert_eq!(c.day, 10);
+    assert_eq!(c.hour, 18);
+    assert_eq!(c.minute, 45);
+    assert_eq!(c.second, 45);
+    assert_eq!(c.weekday, Weekday::Wednesday);
+    assert_eq!(c.day_of_year, 10);
+}
+
+#[test]
+fn decompose_leap_day_and_negative_epoch() {
+    let leap = decompose(parse_iso("2024-12-31T00:00:00Z").unwrap()).unwrap();
+    assert_eq!(leap.day_of_year, 366);
+    assert_eq!(leap.weekday, Weekday::Tuesday);
+
+    let before = decompose(-1).unwrap();
+    assert_eq!((before.year, before.month, before.day), (1969, 12, 31));
+    assert_eq!((before.hour, before.minute, before.second), (23, 59, 59));
+    assert_eq!(before.weekday, Weekday::Wednesday);
+}
+
+#[test]
+fn components_json() {
+    assert_eq!(
+        decompose(0).unwrap().to_json(),
+        r#"{"year":1970,"month":1,"day":1,"hour":0,"minute":0,"second":0,"weekday":"Thursday","day_of_year":1}"#
+    );
+}
+
+#[test]
+fn english_month_and_weekday_names() {
+    assert_eq!(month_name(Month::January), "January");
+    assert_eq!(month_name(Month::December), "December");
+    assert_eq!(weekday_name(Weekday::Monday), "Monday");
+    assert_eq!(weekday_name(Weekday::Sunday), "Sunday");
+    let err = compose(2023, 2, 30, 0, 0, 0).unwrap_err().to_string();
+    assert!(err.contains("February 30, 2023"));
+}
+
+#[test]
+fn compose_valid_fields() {
+    assert_eq!(compose(2024, 1, 10, 12, 0, 0).unwrap(), 1704888000);
+    assert_eq!(compose(1970, 1, 1, 0, 0, 0).unwrap(), 0);
+    assert_eq!(compose(2024, 2, 29, 0, 0, 0).unwrap(), parse_iso("2024-02-29T00:00:00Z").unwrap());
+}
+
+#[test]
+fn compose_invalid_fields() {
+    assert!(matches!(compose(2023, 2, 30, 0, 0, 0).unwrap_err(), EtError::InvalidDate(_)));
+    assert!(matches!(compose(2023, 2, 29, 0, 0, 0).unwrap_err(), EtError::InvalidDate(_)));
+    assert!(matches!(compose(2024, 13, 1, 0, 0, 0).unwrap_err(), EtError::InvalidDate(_)));
+    assert!(matches!(compose(2024, 0, 1, 0, 0, 0).unwrap_err(), EtError::InvalidDate(_)));
+    assert!(matches!(compose(2024, 1, 1, 24, 0, 0).unwrap_err(), EtError::InvalidDate(_)));
+    assert!(matches!(compose(2024, 1, 1, 0, 60, 0).unwrap_err(), EtError::InvalidDate(_)));
+}
+
+#[test]
+fn compose_roundtrips_decompose() {
+    let c = decompose(1704912345).unwrap();
+    let epoch = compose(c.year, c.month, c.day, c.hour, c.minute, c.second).unwrap();
+    assert_eq!(epoch, 1704912345);
+}
+
+// Calendar Queries
+#[test]
+fn days_since_epoch_floors() {
+    assert_eq!(days_since_epoch(0), 0);
+    assert_eq!(days_since_epoch(1704888000), 19732);
+    assert_eq!(days_since_epoch(-1), -1);
+    assert_eq!(days_since_epoch(-86400), -1);
+    assert_eq!(days_since_epoch(-86401), -2);
+}
+
+#[test]
+fn leap_years() {
+    assert!(is_leap_year(2024));
+    assert!(is_leap_year(2000));
+    assert!(!is_leap_year(1900));
+    assert!(!is_leap_year(2023));
+}
+
+#[test]
+fn days_in_months() {
+    assert_eq!(days_in_month(2024, 2).unwrap(), 29);
+    assert_eq!(days_in_month(2023, 2).unwrap(), 28);
+    assert_eq!(days_in_month(2023, 1).unwrap(), 31);
+    assert_eq!(days_in_month(2023, 4).unwrap(), 30);
+    assert!(matches!(days_in_month(2023, 13), Err(EtError::InvalidDate(_))));
+}
+
+#[test]
+fn week_of_month_counts_weekday_occurrences() {
+    // January 2024 starts on a Monday
+    assert_eq!(week_of_month(compose(2024, 1, 1, 0, 0, 0).unwrap()).unwrap(), 1);
+    assert_eq!(week_of_month(compose(2024, 1, 7, 23, 59, 59).unwrap()).unwrap(), 1);
+    // Tuesday the 9th is the second Tuesday
+    assert_eq!(week_of_month(compose(2024, 1, 9, 12, 0, 0).unwrap()).unwrap(), 2);
+    assert_eq!(week_of_month(compose(2024, 1, 14, 0, 0, 0).unwrap()).unwrap(), 2);
+    // Wednesday the 31st is the fifth Wednesday
+    assert_eq!(week_of_month(compose(2024, 1, 31, 0, 0, 0).unwrap()).unwrap(), 5);
+    assert_eq!(week_of_month(compose(2024, 2, 29, 0, 0, 0).unwrap()).unwrap(), 5);
+    assert!(week_of_month(i64::MAX).is_err());
+}
+
+#[test]
+fn iso_week_numbers_across_year_boundaries() {
+    assert_eq!(iso_week(compose(2024, 3, 13, 0, 0, 0).unwrap()).unwrap(), (2024, 11));
+    // Monday 2024-12-30 starts week 1 of 2025
+    assert_eq!(iso_week(compose(2024, 12, 30, 0, 0, 0).unwrap()).unwrap(), (2025, 1));
+    // Sunday 2023-01-01 ends week 52 of 2022
+    assert_eq!(iso_week(compose(2023, 1, 1, 23, 59, 59).unwrap()).unwrap(), (2022, 52));
+    // 2020 has 53 weeks; the last runs into 2021
+    assert_eq!(iso_week(compose(2021, 1, 3, 0, 0, 0).unwrap()).unwrap(), (2020, 53));
+    assert_eq!(iso_week(compose(2021, 1, 4, 0, 0, 0).unwrap()).unwrap(), (2021, 1));
+    assert!(iso_week(i64::MAX).is_err());
+}
+
+#[test]
+fn format_with_wom() {
+    assert_eq!(format_with(1704888000, "wom").unwrap(), "2");
+    // 2024-02-01T02:00:00Z is still January 31 at -05:00
+    let ts = Timestamp::from_seconds(1706752800);
+    assert_eq!(ts.format_with("wom").unwrap(), "1");
+    assert_eq!(ts.format_with_at("wom", parse_offset("-05:00").unwrap()).unwrap(), "5");
+}
+
+#[test]
+fn nth_weekday_of_month() {
+    // 2024-03-18, the 3rd Monday of March
+    assert_eq!(nth_weekday(2024, 3, Weekday::Monday, 3).unwrap(), 1710720000);
+    assert_eq!(nth_weekday(2024, 3, Weekday::Friday, 1).unwrap(), compose(2024, 3, 1, 0, 0, 0).unwrap());
+    // Last and second-to-last Friday of March 2024: the 29th and 22nd
+    assert_eq!(nth_weekday(2024, 3, Weekday::Friday, -1).unwrap(), compose(2024, 3, 29, 0, 0, 0).unwrap());
+    assert_eq!(nth_weekday(2024, 3, Weekday::Friday, -2).unwrap(), compose(2024, 3, 22, 0, 0, 0).unwrap());
+    // February 2024 has five Thursdays but only four Fridays
+    assert_eq!(nth_weekday(2024, 2, Weekday::Thursday, 5).unwrap(), compose(2024, 2, 29, 0, 0, 0).unwrap());
+    assert!(matches!(nth_weekday(2024, 2, Weekday::Friday, 5), Err(EtError::InvalidDate(_))));
+    assert!(matches!(nth_weekday(2024, 2, Weekday::Friday, -5), Err(EtError::InvalidDate(_))));
+    assert!(matches!(nth_weekday(2024, 2, Weekday::Friday, 0), Err(EtError::InvalidDate(_))));
+    assert!(matches!(nth_weekday(2024, 13, Weekday::Friday, 1), Err(EtError::InvalidDate(_))));
+}
+
+#[test]
+fn format_offset_as_signed_hours_and_minutes() {
+    assert_eq!(format_offset(UtcOffset::from_hms(-5, 0, 0).unwrap()), "-05:00");
+    assert_eq!(format_offset(UtcOffset::from_hms(5, 30, 0).unwrap()), "+05:30");
+    assert_eq!(format_offset(UtcOffset::from_hms(0, -30, 0).unwrap()), "-00:30");
+    assert_eq!(format_offset(UtcOffset::UTC), "+00:00");
+    assert_eq!(format_offset(parse_offset("+05:30:15").unwrap()), "+05:30:15");
+}
+
+#[test]
+fn next_and_prev_time_of_day() {
+    let nine = parse_time_of_day("09:00").unwrap();
+    let utc = UtcOffset::UTC;
+    // 2024-01-10T08:00:00Z is before 09:00, so the next one is the same day
+    assert_eq!(next_time_of_day(1704873600, nine, utc).unwrap(), 1704877200);
+    // 2024-01-10T10:00:00Z is past 09:00, so the next one is tomorrow
+    assert_eq!(next_time_of_day(1704880800, nine, utc).unwrap(), 1704963600);
+    assert_eq!(prev_time_of_day(1704880800, nine, utc).unwrap(), 1704877200);
+    assert_eq!(prev_time_of_day(1704873600, nine, utc).unwrap(), 1704790800);
+    // Exactly 09:00 is neither next nor previous
+    assert_eq!(next_time_of_day(1704877200, nine, utc).unwrap(), 1704963600);
+    assert_eq!(prev_time_of_day(1704877200, nine, utc).unwrap(), 1704790800);
+    // 09:00 at +05:30 is 03:30 UTC
+    let ist = parse_offset("+05:30").unwrap();
+    assert_eq!(next_time_of_day(1704880800, nine, ist).unwrap(), 1704943800);
+}
+
+#[test]
+fn parse_time_of_day_forms() {
+    assert_eq!(parse_time_of_day("17:30:15").unwrap(), Time::from_hms(17, 30, 15).unwrap());
+    assert_eq!(parse_time_of_day("00:00").unwrap(), Time::MIDNIGHT);
+    for bad in ["25:00", "9", "09:60", "09:00:00:00", ""] {
+        assert!(matches!(parse_time_of_day(bad), Err(EtError::InvalidDate(_))), "{bad}");
+    }
+}
+
+#[test]
+fn parse_weekday_names() {
+    assert_eq!(parse_weekday("mon").unwrap(), Weekday::Monday);
+    assert_eq!(parse_weekday("Sunday").unwrap(), Weekday::Sunday);
+    assert_eq!(parse_weekday(" THU ").unwrap(), Weekday::Thursday);
+    assert!(matches!(parse_weekday("thurs"), Err(EtError::InvalidWeekday(_))));
+    assert!(matches!(parse_weekday(""), Err(EtError::InvalidWeekday(_))));
+}
+
+// Padding
+#[test]
+fn pad_small_and_large_epochs() {
+    assert_eq!(pad_epoch(42, 10), "0000000042");
+    assert_eq!(pad_epoch(1704912345, 12), "001704912345");
+    // Values already wider than the pad are left untouched
+    assert_eq!(pad_epoch(1704912345, 4), "1704912345");
+}
+
+#[test]
+fn pad_negative_epoch_after_sign() {
+    assert_eq!(pad_epoch(-42, 6), "-00042");
+    assert_eq!(pad_epoch(-86400, 8), "-0086400");
 }
 
 // Fixed Duration Arithmetic
@@ -165,6 +1599,46 @@ fn apply_seconds_overflow() {
     assert!(apply_duration(i64::MIN, Duration::Seconds(-1)).is_err());
 }
 
+#[test]
+fn apply_saturating_clamps_at_both_extremes() {
+    assert_eq!(apply_duration_saturating(i64::MAX, Duration::Seconds(1)), i64::MAX);
+    assert_eq!(apply_duration_saturating(i64::MAX - 10, Duration::Seconds(3600)), i64::MAX);
+    assert_eq!(apply_duration_saturating(i64::MIN, Duration::Seconds(-1)), i64::MIN);
+    assert_eq!(apply_duration_saturating(i64::MIN + 10, Duration::Seconds(-3600)), i64::MIN);
+    // Past the calendar's last year (9999) and before its first (-9999)
+    assert_eq!(apply_duration_saturating(253402300799, Duration::Years(1)), i64::MAX);
+    assert_eq!(apply_duration_saturating(-377705116800, Duration::Months(-1)), i64::MIN);
+    // In range, it matches the checked version
+    let base = 1704912345;
+    for duration in [Duration::Seconds(-90), Duration::Months(1), Duration::Years(-2)] {
+        assert_eq!(apply_duration_saturating(base, duration), apply_duration(base, duration).unwrap());
+    }
+}
+
+#[test]
+fn apply_max_weeks_overflow() {
+    let weeks = Duration::parse("15250284452471w").unwrap();
+    assert_eq!(apply_duration(0, weeks).unwrap(), 15250284452471 * 604800);
+    assert!(matches!(apply_duration(2315008, weeks).unwrap_err(), EtError::Overflow));
+}
+
+#[test]
+fn apply_to_matches_apply_duration() {
+    let base = 1704912345;
+    for duration in [Duration::Seconds(-90), Duration::Months(1), Duration::Years(-2)] {
+        assert_eq!(duration.apply_to(base).unwrap(), apply_duration(base, duration).unwrap());
+    }
+    assert!(Duration::Seconds(1).apply_to(i64::MAX).is_err());
+}
+
+#[test]
+fn apply_to_chains() {
+    let hour = Duration::parse("+1h").unwrap();
+    let minutes = Duration::parse("-30m").unwrap();
+    let result = hour.apply_to(0).and_then(|e| minutes.apply_to(e)).unwrap();
+    assert_eq!(result, 1800);
+}
+
 // Month Arithmetic - Basic
 /// Helper: parse ISO, apply duration, format back to ISO for readable assertions
 fn apply_and_format(iso: &str, duration: Duration) -> String {
@@ -291,6 +1765,31 @@ fn add_13_months() {
     assert_eq!(apply_and_format("2024-01-15T12:00:00Z", Duration::Months(13)), "2025-02-15T12:00:00Z");
 }
 
+// Month Arithmetic - Negative Epochs
+#[test]
+fn months_before_1970_clamp() {
+    let mar31 = parse_iso("1960-03-31T12:00:00Z").unwrap();
+    assert!(mar31 < 0);
+    // 1960 is a leap year
+    let back = apply_duration(mar31, Duration::Months(-1)).unwrap();
+    assert_eq!(back, parse_iso("1960-02-29T12:00:00Z").unwrap());
+    let back = apply_duration(mar31, Duration::Months(-13)).unwrap();
+    assert_eq!(back, parse_iso("1959-02-28T12:00:00Z").unwrap());
+}
+
+#[test]
+fn months_across_the_epoch_and_year_boundaries() {
+    let iso = |s| parse_iso(s).unwrap();
+    let jan31 = iso("1969-01-31T00:00:00Z");
+    assert_eq!(apply_duration(jan31, Duration::Months(-1)).unwrap(), iso("1968-12-31T00:00:00Z"));
+    assert_eq!(apply_duration(jan31, Duration::Months(13)).unwrap(), iso("1970-02-28T00:00:00Z"));
+    let last = iso("1969-12-31T23:59:59Z");
+    assert_eq!(last, -1);
+    assert_eq!(apply_duration(last, Duration::Months(2)).unwrap(), iso("1970-02-28T23:59:59Z"));
+    assert_eq!(apply_duration(0, Duration::Months(-1)).unwrap(), iso("1969-12-01T00:00:00Z"));
+    assert_eq!(apply_duration(0, Duration::Months(-1200)).unwrap(), iso("1870-01-01T00:00:00Z"));
+}
+
 // Year Arithmetic - Basic
 #[test]
 fn add_one_year() {
@@ -305,6 +1804,14 @@ fn subtract_one_year() {
     assert_eq!(apply_and_format("2024-12-31T12:00:00Z", Duration::Years(-1)), "2023-12-31T12:00:00Z");
 }
 
+#[test]
+fn add_decades_and_centuries() {
+    let decades = Duration::parse("2decade").unwrap();
+    assert_eq!(apply_and_format("2024-01-15T12:00:00Z", decades), "2044-01-15T12:00:00Z");
+    let century = Duration::parse("-1century").unwrap();
+    assert_eq!(apply_and_format("2024-02-29T12:00:00Z", century), "1924-02-29T12:00:00Z");
+}
+
 #[test]
 fn add_multiple_years() {
     assert_eq!(apply_and_format("2024-01-15T12:00:00Z", Duration::Years(5)), "2029-01-15T12:00:00Z");
@@ -345,6 +1852,93 @@ fn century_leap_year_rules() {
     assert_eq!(apply_and_format("2096-02-29T12:00:00Z", Duration::Years(4)), "2100-02-28T12:00:00Z");
 }
 
+// Year Arithmetic - Exact Days
+#[test]
+fn exact_days_year_counts_the_days_it_spans() {
+    let days = |iso, years| Duration::Years(years).in_exact_days(parse_iso(iso).unwrap()).unwrap();
+    let fixed = |value| Duration::Fixed { value, unit: FixedUnit::Days };
+    assert_eq!(days("2024-02-29T12:00:00Z", 1), fixed(366));
+    assert_eq!(days("2024-02-29T12:00:00Z", -1), fixed(-365));
+    assert_eq!(days("2024-01-15T00:00:00Z", 1), fixed(366));
+    assert_eq!(days("2024-07-01T00:00:00Z", 1), fixed(365));
+    assert_eq!(days("2024-02-29T12:00:00Z", 4), fixed(1461));
+    assert_eq!(Duration::Months(1).in_exact_days(0).unwrap(), Duration::Months(1));
+}
+
+#[test]
+fn exact_days_year_lands_feb_29_on_mar_1() {
+    let epoch = parse_iso("2024-02-29T12:00:00Z").unwrap();
+    let duration = Duration::Years(1).in_exact_days(epoch).unwrap();
+    assert_eq!(format_iso(apply_duration(epoch, duration).unwrap()).unwrap(), "2025-03-01T12:00:00Z");
+    let epoch = parse_iso("2024-07-01T00:00:00Z").unwrap();
+    let duration = Duration::Years(1).in_exact_days(epoch).unwrap();
+    assert_eq!(apply_duration(epoch, duration).unwrap(), apply_duration(epoch, Duration::Years(1)).unwrap());
+}
+
+#[test]
+fn year_mode_from_str() {
+    assert_eq!("calendar".parse::<YearMode>().unwrap(), YearMode::Calendar);
+    assert_eq!("exact-days".parse::<YearMode>().unwrap(), YearMode::ExactDays);
+    assert_eq!(YearMode::default(), YearMode::Calendar);
+    assert!(matches!("exact".parse::<YearMode>(), Err(EtError::InvalidYearMode(_))));
+}
+
+// Clamping Reports
+#[test]
+fn reported_clamping_jan_31_plus_month() {
+    // 2024-01-31T00:00:00Z + 1M clamps to Feb 29
+    let result = apply_duration_reported(1706659200, Duration::Months(1)).unwrap();
+    assert_eq!(result, (1709164800, true));
+}
+
+#[test]
+fn reported_no_clamping_jan_15_plus_month() {
+    // 2024-01-15T00:00:00Z + 1M is Feb 15
+    let result = apply_duration_reported(1705276800, Duration::Months(1)).unwrap();
+    assert_eq!(result, (1707955200, false));
+}
+
+#[test]
+fn reported_clamping_years_and_fixed() {
+    // 2024-02-29T00:00:00Z + 1Y clamps to 2025-02-28
+    let result = apply_duration_reported(1709164800, Duration::Years(1)).unwrap();
+    assert_eq!(result, (1740700800, true));
+    assert!(!apply_duration_reported(1709164800, Duration::Years(4)).unwrap().1);
+    assert_eq!(apply_duration_reported(0, Duration::Seconds(86400)).unwrap(), (86400, false));
+}
+
+// Explaining Arithmetic
+#[test]
+fn explain_reports_clamping() {
+    // 2024-01-31T00:00:00Z + 1M clamps to Feb 29
+    let report = explain_duration(1706659200, Duration::Months(1)).unwrap();
+    assert!(report.clamped);
+    assert_eq!(report.after, 1709164800);
+    assert_eq!(
+        report.describe().unwrap(),
+        "Adding 1 calendar month: 2024-01-31T00:00:00Z → 2024-02-29T00:00:00Z, clamping day 31 to 29"
+    );
+}
+
+#[test]
+fn explain_without_clamping() {
+    // 2024-01-15T00:00:00Z + 1M keeps the day
+    let report = explain_duration(1705276800, Duration::Months(1)).unwrap();
+    assert!(!report.clamped);
+    assert_eq!(
+        report.describe().unwrap(),
+        "Adding 1 calendar month: 2024-01-15T00:00:00Z → 2024-02-15T00:00:00Z"
+    );
+
+    let report = explain_duration(86400, Duration::Seconds(-86400)).unwrap();
+    assert!(!report.clamped);
+    assert!(report.describe().unwrap().starts_with("Subtracting 86400 seconds: "));
+
+    // A duration keeps the unit it was written in
+    let report = explain_duration(0, Duration::parse("-1d").unwrap()).unwrap();
+    assert!(report.describe().unwrap().starts_with("Subtracting 1 day: "));
+}
+
 // Time Preservation
 #[test]
 fn month_addition_preserves_time() {
@@ -358,6 +1952,91 @@ fn year_addition_preserves_time() {
     assert_eq!(apply_and_format("2024-01-15T08:30:45Z", Duration::Years(1)), "2025-01-15T08:30:45Z");
 }
 
+// Date-Time Variants
+#[test]
+fn parse_iso_dt_matches_parse_iso() {
+    for input in ["2024-01-10T12:00:00Z", "2024-01-10T12:00:00.987654321+05:30", "1969-12-31T23:59:59Z", "epoch"] {
+        let dt = parse_iso_dt(input).unwrap();
+        assert_eq!(dt.unix_timestamp(), parse_iso(input).unwrap());
+        assert_eq!(dt.unix_timestamp_nanos(), parse_iso_nanos(input).unwrap());
+    }
+    // The offset and sub-second part survive
+    let dt = parse_iso_dt("2024-01-10T12:00:00.5+05:30").unwrap();
+    assert_eq!(dt.offset(), UtcOffset::from_hms(5, 30, 0).unwrap());
+    assert_eq!(dt.millisecond(), 500);
+    assert!(matches!(parse_iso_dt("2024-01-10T12:00:00"), Err(EtError::MissingTimezone(_))));
+}
+
+#[test]
+fn apply_duration_dt_matches_apply_duration() {
+    let durations = [
+        Duration::Seconds(90),
+        Duration::Seconds(-86400),
+        Duration::Months(1),
+        Duration::Months(-13),
+        Duration::Years(1),
+    ];
+    for input in ["2024-01-31T08:30:45Z", "2024-02-29T00:00:00Z", "1969-12-31T23:59:59Z"] {
+        let dt = parse_iso_dt(input).unwrap();
+        let epoch = parse_iso(input).unwrap();
+        for duration in durations {
+            assert_eq!(
+                apply_duration_dt(dt, duration).unwrap().unix_timestamp(),
+                apply_duration(epoch, duration).unwrap(),
+                "{input} {duration:?}"
+            );
+        }
+    }
+}
+
+#[test]
+fn apply_duration_dt_keeps_offset_and_precision() {
+    let dt = parse_iso_dt("2024-01-31T23:30:00.25-05:00").unwrap();
+    let later = apply_duration_dt(dt, Duration::Months(1)).unwrap();
+    // The calendar move happens in the value's own offset
+    assert_eq!(later, parse_iso_dt("2024-02-29T23:30:00.25-05:00").unwrap());
+    assert_eq!(later.offset(), dt.offset());
+    assert_eq!(later.millisecond(), 250);
+    assert!(matches!(apply_duration_dt(dt, Duration::Years(10000)), Err(EtError::Overflow)));
+}
+
+// 32-bit Limits
+#[test]
+fn fits_in_32_bits() {
+    assert!(fits_in_i32(2147483647));
+    assert!(!fits_in_i32(2147483648));
+    assert!(fits_in_u32(2147483648));
+    assert!(fits_in_u32(4294967295));
+    assert!(!fits_in_u32(4294967296));
+    assert!(fits_in_i32(-1));
+    assert!(!fits_in_u32(-1));
+}
+
+// Config File
+#[test]
+fn config_parse_settings() {
+    let text = "# comment\n\nformat = \"[year] # not a comment\"  # comment\nprecision = 'ms'\ntimezone = \"+05:30\"\nweek_start = \"sun\"\n";
+    let config = Config::parse(text).unwrap();
+    assert_eq!(config.format.as_deref(), Some("[year] # not a comment"));
+    assert_eq!(config.precision, Some(Precision::Millis));
+    assert_eq!(config.timezone, Some(parse_offset("+05:30").unwrap()));
+    assert_eq!(config.week_start, Some(WeekStart::Sunday));
+    assert_eq!(Config::parse("").unwrap(), Config::default());
+}
+
+#[test]
+fn config_parse_rejects_bad_lines() {
+    assert!(matches!(Config::parse("color = never"), Err(EtError::InvalidConfig(_))));
+    assert!(matches!(Config::parse("precision"), Err(EtError::InvalidConfig(_))));
+    assert!(matches!(Config::parse("timezone = \"+05:30"), Err(EtError::InvalidConfig(_))));
+    assert!(matches!(Config::parse("precision = ms"), Err(EtError::InvalidConfig(_))));
+    let err = Config::parse("\nweek_start = \"wed\"").unwrap_err().to_string();
+    assert!(err.starts_with("invalid config file: line 2:"), "{err}");
+    // Tables parse as TOML, but a setting inside one is still unknown
+    let err = Config::parse("precision = \"ms\"\n[output]\nformat = \"iso\"").unwrap_err().to_string();
+    assert!(err.starts_with("invalid config file: line 2:"), "{err}");
+}
+
 // is_duration Tests
 #[test]
 fn is_duration_fixed_units() {
@@ -365,6 +2044,20 @@ fn is_duration_fixed_units() {
     assert!(is_duration("-7d"));
     assert!(is_duration("30s"));
     assert!(is_duration("2w"));
+    // Epochs with a unit suffix are not durations
+    assert!(!is_duration("1704912345s"));
+    assert!(!is_duration("1704912345000ms"));
+    assert!(!is_duration("-5ns"));
+    assert!(is_duration("+1704912345s"));
+}
+
+#[test]
+fn is_duration_word_forms() {
+    assert!(is_duration("1fortnight"));
+    assert!(is_duration("3hours"));
+    assert!(is_duration("1minute"));
+    assert!(is_duration("2days"));
+    assert!(is_duration("1week"));
 }
 
 #[test]
@@ -376,6 +2069,24 @@ fn is_duration_calendar_units() {
     assert!(is_duration("+2Y"));
     assert!(is_duration("-1Y"));
     assert!(is_duration("1y"));
+    assert!(is_duration("2decade"));
+    assert!(is_duration("1century"));
+    assert!(is_duration("1c"));
+}
+
+#[test]
+fn is_duration_fractions() {
+    assert!(is_duration("12.34d"));
+    assert!(is_duration("-1.5h"));
+    assert!(is_duration("0.5M"));
+}
+
+#[test]
+fn is_duration_clock_notation() {
+    assert!(is_duration("01:30:00"));
+    assert!(is_duration("-90:00"));
+    assert!(!is_duration("1:2:3:4"));
+    assert!(!is_duration("12:"));
 }
 
 #[test]
@@ -384,6 +2095,25 @@ fn is_duration_false_for_epoch() {
     assert!(!is_duration("0"));
 }
 
+#[test]
+fn is_duration_rejects_interior_junk() {
+    assert!(!is_duration("1.2.3s"));
+    assert!(!is_duration("1.h"));
+    assert!(!is_duration(".5h"));
+    assert!(!is_duration("1a2s"));
+    assert!(!is_duration("+1a2s"));
+    assert!(!is_duration("++5s"));
+    assert!(!is_duration("+"));
+    assert!(!is_duration("d"));
+}
+
+#[test]
+fn is_duration_signed_bare_number() {
+    assert!(is_duration("-3600"));
+    assert!(is_duration("+60"));
+    assert!(is_duration("  -7d  "));
+}
+
 #[test]
 fn is_duration_false_for_keywords() {
     assert!(!is_duration("now"));
@@ -400,6 +2130,16 @@ fn roundtrip_epoch_iso() {
     }
 }
 
+#[test]
+fn roundtrip_epoch_iso_millis() {
+    for millis in [0i64, 1, 123, 999, 1704888000123, 1704888000999, -1, -86400001] {
+        let iso = format_iso_millis(millis).unwrap();
+        assert_eq!(parse_iso_millis(&iso).unwrap(), millis, "roundtrip of {iso}");
+    }
+    assert_eq!(format_iso_millis(1704888000123).unwrap(), "2024-01-10T12:00:00.123Z");
+    assert_eq!(format_iso_millis(-1).unwrap(), "1969-12-31T23:59:59.999Z");
+}
+
 #[test]
 fn roundtrip_month_arithmetic() {
     // Adding and subtracting same months should return to original (when no clamping)
@@ -418,5 +2158,57 @@ fn roundtrip_year_arithmetic() {
     assert_eq!(original, back);
 }
 
+// Error Codes
+#[test]
+fn error_codes_are_stable() {
+    let cases = [
+        (EtError::InvalidEpoch("x".into()), "invalid_epoch"),
+        (EtError::EpochOutOfRange(MAX_EPOCH + 1), "epoch_out_of_range"),
+        (EtError::InvalidDuration("x".into()), "invalid_duration"),
+        (EtError::UnsupportedUnit("x".into()), "unsupported_unit"),
+        (EtError::InvalidIso("x".into()), "invalid_iso"),
+        (EtError::MissingTimezone("x".into()), "missing_timezone"),
+        (EtError::UnrecognizedTimestamp("x".into()), "unrecognized_timestamp"),
+        (EtError::InvalidDate("x".into()), "invalid_date"),
+        (EtError::InvalidFormat("x".into()), "invalid_format"),
+        (EtError::InvalidOffset("x".into()), "invalid_offset"),
+        (EtError::IndeterminateOffset, "indeterminate_offset"),
+        (EtError::UnsupportedPrecision("x".into()), "unsupported_precision"),
+        (EtError::UnsupportedPeriod("x".into()), "unsupported_period"),
+        (EtError::InvalidWeekStart("x".into()), "invalid_week_start"),
+        (EtError::InvalidYearMode("x".into()), "invalid_year_mode"),
+        (EtError::InvalidWeekday("x".into()), "invalid_weekday"),
+        (EtError::InvalidRange("x".into()), "invalid_range"),
+        (EtError::Overflow, "overflow"),
+        (EtError::NoInput, "no_input"),
+        (EtError::TooManyLines(10), "too_many_lines"),
+        (EtError::InvalidLine(1, "x".into()), "invalid_line"),
+        (EtError::InvalidDurationAt(1, "x".into()), "invalid_duration_at"),
+        (EtError::InvalidConfig("x".into()), "invalid_config"),
+        (EtError::InFile("in.txt".into(), "x".into()), "in_file"),
+        (EtError::Io(std::io::Error::other("x")), "io"),
+    ];
+    for (error, code) in cases {
+        assert_eq!(error.code(), code, "{error:?}");
+    }
+}
+
+#[test]
+fn error_to_json_has_code_and_message() {
+    assert_eq!(EtError::Overflow.to_json(), r#"{"code":"overflow","message":"arithmetic overflow"}"#);
+    assert_eq!(
+        EtError::InvalidEpoch("12ab".into()).to_json(),
+        r#"{"code":"invalid_epoch","message":"invalid epoch timestamp: 12ab"}"#
+    );
+    assert_eq!(
+        EtError::InvalidDuration("\"1h\\\n".into()).to_json(),
+        r#"{"code":"invalid_duration","message":"invalid duration: \"1h\\\n"}"#
+    );
+    assert_eq!(
+        EtError::InvalidFormat("\u{1}".into()).to_json(),
+        r#"{"code":"invalid_format","message":"invalid format: \u0001"}"#
+    );
+}
+
 // Note: Roundtrip with clamping dates (like Feb 29) is NOT guaranteed to work
 // because information is lost during clamping. This is expected behaviour.